@@ -1,10 +1,177 @@
-use std::io::Error;
+use std::ops::Range;
 
-use super::header::MP3AudioFrameHeader;
+use super::header::{Layer, MP3AudioFrameHeader, MPEGVersion};
+use super::vbr::VbrHeader;
+use crate::error::DecodeError;
+use crate::utils::BitReader;
 
-#[derive(Debug)]
-pub struct MP3SideInfo {}
-impl MP3SideInfo {}
+/// The largest legal free-format frame: 640 kbps at the lowest sample rate (MPEG-2 Layer II at
+/// 16kHz, or MPEG-2.5 Layer III at 8kHz), plus a padding slot
+pub(crate) const MAX_FREE_FORMAT_LENGTH: usize = 144 * 640_000 / 16_000 + 1;
+
+/// The Layer III side information, which tells the decoder how to unpack the main data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MP3SideInfo {
+    /// How many bytes before this frame's main data the first granule begins (see
+    /// `MP3AudioFrame::main_data_begin`)
+    pub main_data_begin: u16,
+
+    pub private_bits: u8,
+
+    /// Per channel, whether each of the 4 scalefactor bands reuses the first granule's
+    /// scalefactors in the second granule. Always false for MPEG-2/2.5, which has 1 granule
+    pub scfsi: Vec<[bool; 4]>,
+
+    /// Indexed by granule, then channel
+    pub granules: Vec<Vec<GranuleInfo>>,
+}
+
+/// The side information of one channel in one granule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GranuleInfo {
+    /// Number of main data bits used for the scalefactors and Huffman coded data
+    pub part2_3_length: u16,
+    pub big_values: u16,
+    pub global_gain: u8,
+
+    /// 4 bits for MPEG-1, 9 bits for MPEG-2/2.5
+    pub scalefac_compress: u16,
+
+    pub window_switching_flag: bool,
+
+    /// 0 (normal) unless `window_switching_flag` is set. 1 is start, 2 is short, 3 is stop
+    pub block_type: u8,
+    pub mixed_block_flag: bool,
+
+    /// Only the first 2 are used when `window_switching_flag` is set
+    pub table_select: [u8; 3],
+
+    /// Only used when `window_switching_flag` is set
+    pub subblock_gain: [u8; 3],
+
+    /// Implied by the block type when `window_switching_flag` is set
+    pub region0_count: u8,
+    pub region1_count: u8,
+
+    /// Always false for MPEG-2/2.5, where it is derived from `scalefac_compress` instead
+    pub preflag: bool,
+    pub scalefac_scale: bool,
+    pub count1table_select: bool,
+}
+
+impl MP3SideInfo {
+    /// Parses the side information from the bytes following the header (and CRC, if present)
+    ///
+    /// Structure (MPEG-1 / MPEG-2 and 2.5):
+    /// main_data_begin    => 9 / 8 bits
+    /// private_bits       => 5 (mono) or 3 / 1 (mono) or 2 bits
+    /// scfsi              => 4 bits per channel / absent
+    /// granule info       => 59 / 63 bits per channel, for each of the 2 / 1 granules
+    pub fn from_bytes(header: &MP3AudioFrameHeader, bytes: &[u8]) -> Result<Self, DecodeError> {
+        let length = header.side_info_length();
+        if bytes.len() < length {
+            return Err(DecodeError::TruncatedFrame {
+                needed: length,
+                got: bytes.len(),
+            });
+        }
+
+        let is_mpeg1 = header.mpeg_version() == MPEGVersion::Mpeg1;
+        let channel_count = header.channel_mode().channel_count() as usize;
+        let mut reader = BitReader::new(&bytes[..length]);
+
+        let main_data_begin = reader.read(if is_mpeg1 { 9 } else { 8 }) as u16;
+        let private_bits = match (is_mpeg1, channel_count) {
+            (true, 1) => reader.read(5),
+            (true, _) => reader.read(3),
+            (false, 1) => reader.read(1),
+            (false, _) => reader.read(2),
+        } as u8;
+
+        let scfsi = (0..channel_count)
+            .map(|_| match is_mpeg1 {
+                true => [(); 4].map(|_| reader.read_bool()),
+                false => [false; 4],
+            })
+            .collect();
+
+        let granules = (0..header.granule_count())
+            .map(|_| {
+                (0..channel_count)
+                    .map(|_| GranuleInfo::from_reader(&mut reader, is_mpeg1))
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self {
+            main_data_begin,
+            private_bits,
+            scfsi,
+            granules,
+        })
+    }
+}
+
+impl GranuleInfo {
+    fn from_reader(reader: &mut BitReader, is_mpeg1: bool) -> Self {
+        let part2_3_length = reader.read(12) as u16;
+        let big_values = reader.read(9) as u16;
+        let global_gain = reader.read(8) as u8;
+        let scalefac_compress = reader.read(if is_mpeg1 { 4 } else { 9 }) as u16;
+        let window_switching_flag = reader.read_bool();
+
+        let mut block_type = 0;
+        let mut mixed_block_flag = false;
+        let mut table_select = [0; 3];
+        let mut subblock_gain = [0; 3];
+        let region0_count;
+        let region1_count;
+        if window_switching_flag {
+            block_type = reader.read(2) as u8;
+            mixed_block_flag = reader.read_bool();
+            for table in &mut table_select[..2] {
+                *table = reader.read(5) as u8;
+            }
+            for gain in &mut subblock_gain {
+                *gain = reader.read(3) as u8;
+            }
+
+            // The region boundaries aren't coded, the rest of big_values is region 1
+            region0_count = match (block_type, mixed_block_flag) {
+                (2, false) => 8,
+                (_, _) => 7,
+            };
+            region1_count = 36;
+        } else {
+            for table in &mut table_select {
+                *table = reader.read(5) as u8;
+            }
+            region0_count = reader.read(4) as u8;
+            region1_count = reader.read(3) as u8;
+        }
+
+        let preflag = is_mpeg1 && reader.read_bool();
+        let scalefac_scale = reader.read_bool();
+        let count1table_select = reader.read_bool();
+
+        Self {
+            part2_3_length,
+            big_values,
+            global_gain,
+            scalefac_compress,
+            window_switching_flag,
+            block_type,
+            mixed_block_flag,
+            table_select,
+            subblock_gain,
+            region0_count,
+            region1_count,
+            preflag,
+            scalefac_scale,
+            count1table_select,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct MP3AudioFrame<'a> {
@@ -13,32 +180,275 @@ pub struct MP3AudioFrame<'a> {
 
     /// The total size of this frame
     pub frame_length: u32,
+
+    /// Position of the frame's sync word in the buffer it was parsed from. Set by
+    /// `FrameParser`; frames parsed on their own with `from_bytes` are at offset 0
+    pub offset: usize,
+
+    /// Only present for Layer III frames, if `data` is long enough to hold it
+    pub side_info: Option<MP3SideInfo>,
 }
 impl<'a> MP3AudioFrame<'a> {
-    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
-        let header = MP3AudioFrameHeader::from_bytes(&bytes[..4].try_into().unwrap())?;
-
-        let padding = match header.has_padding {
-            true => 1,
-            false => 0,
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let Some(header_bytes) = bytes.first_chunk::<4>() else {
+            return Err(DecodeError::TruncatedFrame {
+                needed: 4,
+                got: bytes.len(),
+            });
         };
+        let header = MP3AudioFrameHeader::from_bytes(header_bytes)?;
 
-        let samples_per_frame = header.layer.get_samples_per_frame();
-        let frame_length = (samples_per_frame as u32)
-            * ((header.bitrate as u32) / (header.sample_rate as u32))
-            + padding;
+        // The data begins after the CRC, if present
+        let data_start = 4 + header.crc_length();
+        if bytes.len() < data_start {
+            return Err(DecodeError::TruncatedFrame {
+                needed: data_start,
+                got: bytes.len(),
+            });
+        }
 
-        println!(
-            "Frame length {frame_length}: {samples_per_frame} {} {} {padding}",
-            header.bitrate, header.sample_rate
+        let frame_length = Self::compute_length(&header, bytes)?;
+
+        log::trace!(
+            "Frame length {frame_length}: {:?} {}",
+            header.bitrate,
+            header.sample_rate
         );
+        let data = &bytes[data_start..];
+        let side_info = match header.layer {
+            Layer::Layer3 => MP3SideInfo::from_bytes(&header, data).ok(),
+            _ => None,
+        };
+
         Ok(Self {
             header,
+            data,
             frame_length,
-            data: &bytes[4..],
+            offset: 0,
+            side_info,
         })
     }
+
+    /// Computes the length of the frame that begins at `bytes[0]` and has the given header
+    pub(crate) fn compute_length(
+        header: &MP3AudioFrameHeader,
+        bytes: &[u8],
+    ) -> Result<u32, DecodeError> {
+        match header.bitrate {
+            Some(bitrate) => {
+                let (coefficient, slot_size) = slot_layout(header);
+                let padding = header.has_padding as u32;
+
+                // Multiply before dividing so the integer division doesn't truncate to 0
+                Ok((coefficient * bitrate / header.sample_rate as u32 + padding) * slot_size)
+            }
+            None => Self::measure_free_format_length(header, bytes).ok_or(
+                DecodeError::UnterminatedFreeFormatFrame {
+                    max_length: MAX_FREE_FORMAT_LENGTH,
+                },
+            ),
+        }
+    }
+
+    /// Measures the length of a free-format frame (one with bitrate index 0) as the distance
+    /// to the next free-format frame header with the same version, layer and sample rate
+    ///
+    /// Only the first `MAX_FREE_FORMAT_LENGTH` bytes are searched. If there is no such header,
+    /// the frame is assumed to run to the end of `bytes` when that is within the limit (ie it
+    /// is the last frame), and `None` is returned otherwise
+    fn measure_free_format_length(header: &MP3AudioFrameHeader, bytes: &[u8]) -> Option<u32> {
+        let min_length = 4 + header.crc_length() + header.side_info_length();
+        let is_next_header = |window: &[u8]| {
+            window[0] == 0xFF
+                && MP3AudioFrameHeader::from_bytes(window.try_into().unwrap()).is_ok_and(|next| {
+                    next.bitrate.is_none()
+                        && next.mpeg_version() == header.mpeg_version()
+                        && next.layer == header.layer
+                        && next.sample_rate == header.sample_rate
+                })
+        };
+
+        let search_end = bytes.len().min(MAX_FREE_FORMAT_LENGTH + 4);
+        let next_header = bytes
+            .get(min_length..search_end)
+            .and_then(|rest| rest.windows(4).position(is_next_header));
+
+        match next_header {
+            Some(position) => Some((min_length + position) as u32),
+            None if bytes.len() <= MAX_FREE_FORMAT_LENGTH => Some(bytes.len() as u32),
+            None => None,
+        }
+    }
+
+    /// Returns the length of the shortest frame with the same version and layer as `header`, ie
+    /// at the lowest bitrate and highest sample rate, without padding
+    pub(crate) fn min_frame_length(header: &MP3AudioFrameHeader) -> u32 {
+        let version = header.mpeg_version();
+        let bitrate = version
+            .get_bitrate(header.layer, 1)
+            .ok()
+            .flatten()
+            .expect("bitrate index 1 is defined for every version and layer");
+        let sample_rate = version
+            .get_sampling_rate(1)
+            .expect("sampling rate index 1 is defined for every version");
+
+        let (coefficient, slot_size) = slot_layout(header);
+        coefficient * bitrate / sample_rate as u32 * slot_size
+    }
+
+    /// Returns the bitrate (in bps). For free-format frames, which don't signal their bitrate,
+    /// it is derived from the measured frame length
+    pub fn bitrate(&self) -> u32 {
+        Self::compute_bitrate(&self.header, self.frame_length)
+    }
+
+    /// Returns the bitrate (in bps) of a frame with the given header and length
+    pub(crate) fn compute_bitrate(header: &MP3AudioFrameHeader, frame_length: u32) -> u32 {
+        if let Some(bitrate) = header.bitrate {
+            return bitrate;
+        }
+
+        let (coefficient, slot_size) = slot_layout(header);
+        let padding = header.has_padding as u64;
+        let bitrate = ((frame_length / slot_size) as u64).saturating_sub(padding)
+            * header.sample_rate as u64
+            / coefficient as u64;
+        bitrate.min(u32::MAX as u64) as u32
+    }
+
+    /// Returns the range of bytes this frame occupies in the buffer it was parsed from, eg to
+    /// copy a run of frames into a new file without re-encoding
+    pub fn byte_range(&self) -> Range<usize> {
+        self.offset..self.offset + self.frame_length as usize
+    }
+
+    /// Verifies the frame's CRC-16, given the bytes of the frame (starting at the sync word)
+    ///
+    /// The CRC covers the last 16 bits of the header and the side information. Returns
+    /// `Ok(true)` for frames that aren't CRC protected. Only Layer III frames are supported,
+    /// as the protected bits of Layer I and II frames depend on their bit allocation.
+    pub fn verify_crc(&self, bytes: &[u8]) -> Result<bool, DecodeError> {
+        if !self.header.has_crc() {
+            return Ok(true);
+        }
+
+        if self.header.layer != Layer::Layer3 {
+            return Err(DecodeError::UnsupportedCrcLayer);
+        }
+
+        let end_of_side_info = 6 + self.header.side_info_length();
+        if bytes.len() < end_of_side_info {
+            return Err(DecodeError::TruncatedFrame {
+                needed: end_of_side_info,
+                got: bytes.len(),
+            });
+        }
+
+        let expected = u16::from_be_bytes([bytes[4], bytes[5]]);
+        let crc = crc16(&[&bytes[2..4], &bytes[6..end_of_side_info]]);
+
+        Ok(crc == expected)
+    }
+
+    /// Returns the Xing/Info or VBRI header stored in this frame, if any
+    ///
+    /// Only the first frame of a stream is expected to hold one. Its frame count gives an
+    /// accurate duration for VBR streams, ie `frame_count * samples_per_frame / sample_rate`
+    pub fn vbr_header(&self) -> Option<VbrHeader> {
+        VbrHeader::from_frame_data(&self.header, self.data)
+    }
+
+    /// Returns the index of the first sample of audio frame `n` in the decoded output, once the
+    /// encoder delay given by this frame's LAME tag has been trimmed
+    ///
+    /// This is expected to be the first frame of the stream. Frame 0 is the first frame holding
+    /// audio, ie the one after a Xing/Info header. Samples within the delay are trimmed, so the
+    /// frames they cover begin at 0. Without a LAME tag, nothing is trimmed.
+    pub fn sample_offset_of_frame(&self, n: usize) -> u64 {
+        let delay = self
+            .vbr_header()
+            .and_then(|vbr| vbr.delay_and_padding())
+            .map_or(0, |(delay, _)| delay as u64);
+
+        (n as u64 * self.header.samples_per_frame() as u64).saturating_sub(delay)
+    }
+
+    /// Returns the undecoded main data of this frame, ie the bytes after the header, CRC
+    /// and side information, up to the end of the frame
+    ///
+    /// The main data holds `header.granule_count()` granules for each channel. Note that for
+    /// Layer III, part of a granule may live in a previous frame (the bit reservoir).
+    pub fn main_data(&self) -> &'a [u8] {
+        let start = self.header.side_info_length();
+        let end = (self.frame_length as usize)
+            .saturating_sub(4 + self.header.crc_length())
+            .min(self.data.len());
+
+        self.data.get(start..end).unwrap_or_default()
+    }
+
+    /// Returns how many bytes before this frame's main data its first granule begins, ie how much
+    /// of the bit reservoir (the main data of previous frames) it uses
+    ///
+    /// This is the first field of the side information: 9 bits for MPEG-1, 8 bits for MPEG-2/2.5.
+    /// Always 0 for Layer I and II frames, which have no reservoir.
+    pub fn main_data_begin(&self) -> u16 {
+        if self.header.layer != Layer::Layer3 {
+            return 0;
+        }
+
+        match (self.header.mpeg_version(), self.data) {
+            (MPEGVersion::Mpeg1, [first, second, ..]) => {
+                ((*first as u16) << 1) | (*second as u16 >> 7)
+            }
+            (_, [first, ..]) => *first as u16,
+            (_, []) => 0,
+        }
+    }
+
+    /// Checks that the payload is large enough to hold the side information
+    ///
+    /// This is a cheap structural check that catches truncated frames before decoding
+    pub fn validate_side_info(&self) -> Result<(), DecodeError> {
+        let header_length = 4 + self.header.crc_length();
+        let needed = header_length + self.header.side_info_length();
+        let got = header_length + self.data.len();
+        if got < needed {
+            return Err(DecodeError::TruncatedFrame { needed, got });
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the coefficient relating the bitrate to the number of slots in a frame, ie
+/// `slots = coefficient * bitrate / sample_rate + padding`, and the size of a slot in bytes
+///
+/// Layer I frames are made up of 4-byte slots, Layer II and III of 1-byte slots
+fn slot_layout(header: &MP3AudioFrameHeader) -> (u32, u32) {
+    match (header.layer, header.mpeg_version()) {
+        (Layer::Layer1, _) => (12, 4),
+        (Layer::Layer3, MPEGVersion::Mpeg2 | MPEGVersion::Mpeg2_5) => (72, 1),
+        (_, _) => (144, 1),
+    }
 }
+
+/// Computes the CRC-16 (polynomial 0x8005, initial value 0xFFFF) used by MPEG audio frames
+fn crc16(chunks: &[&[u8]]) -> u16 {
+    let mut crc: u16 = 0xFFFF;
+    for byte in chunks.iter().flat_map(|chunk| chunk.iter()) {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = match crc & 0x8000 {
+                0 => crc << 1,
+                _ => (crc << 1) ^ 0x8005,
+            };
+        }
+    }
+    crc
+}
+
 impl<'a> std::fmt::Display for MP3AudioFrame<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -48,3 +458,310 @@ impl<'a> std::fmt::Display for MP3AudioFrame<'a> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_length_layer3() {
+        // MPEG-1 Layer III, 128kbps, 44.1kHz
+        let frame = MP3AudioFrame::from_bytes(&[0xFF, 0xFB, 0x90, 0x64]).unwrap();
+        assert_eq!(frame.frame_length, 417);
+
+        // Same as above, with padding
+        let frame = MP3AudioFrame::from_bytes(&[0xFF, 0xFB, 0x92, 0x64]).unwrap();
+        assert_eq!(frame.frame_length, 418);
+    }
+
+    #[test]
+    fn test_frame_length_layer3_mpeg2() {
+        // MPEG-2 Layer III, 64kbps, 22.05kHz
+        let frame = MP3AudioFrame::from_bytes(&[0xFF, 0xF3, 0x80, 0x64]).unwrap();
+        assert_eq!(frame.frame_length, 208);
+    }
+
+    #[test]
+    fn test_frame_length_layer1() {
+        // MPEG-1 Layer I, 288kbps, 44.1kHz
+        let frame = MP3AudioFrame::from_bytes(&[0xFF, 0xFF, 0x90, 0x64]).unwrap();
+        assert_eq!(frame.frame_length, 312);
+
+        // Same as above, with padding (one 4-byte slot)
+        let frame = MP3AudioFrame::from_bytes(&[0xFF, 0xFF, 0x92, 0x64]).unwrap();
+        assert_eq!(frame.frame_length, 316);
+    }
+
+    #[test]
+    fn test_validate_side_info_truncated() {
+        // MPEG-1 Layer III stereo needs 32 bytes of side info
+        let mut bytes = vec![0xFF, 0xFB, 0x90, 0x64];
+        bytes.extend_from_slice(&[0x00; 20]);
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+
+        let err = frame.validate_side_info().unwrap_err();
+        assert_eq!(
+            err,
+            DecodeError::TruncatedFrame {
+                needed: 36,
+                got: 24
+            }
+        );
+    }
+
+    #[test]
+    fn test_validate_side_info() {
+        let mut bytes = vec![0xFF, 0xFB, 0x90, 0x64];
+        bytes.extend_from_slice(&[0x00; 32]);
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+
+        assert!(frame.validate_side_info().is_ok());
+    }
+
+    #[test]
+    fn test_main_data() {
+        let header_bytes = [0xFF, 0xFB, 0x90, 0x64];
+        let frame_length = MP3AudioFrame::from_bytes(&header_bytes)
+            .unwrap()
+            .frame_length as usize;
+
+        let mut bytes = vec![0x00; frame_length];
+        bytes[..4].copy_from_slice(&header_bytes);
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+
+        // 4 byte header, no CRC, 32 bytes of side info
+        assert_eq!(frame.main_data().len(), frame_length - 4 - 32);
+    }
+
+    #[test]
+    fn test_main_data_begin() {
+        // MPEG-1: 9 bits, 0b1_0110_0101
+        let bytes = [0xFF, 0xFB, 0x90, 0x64, 0b1011_0010, 0b1000_0000];
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+        assert_eq!(frame.main_data_begin(), 0b1_0110_0101);
+
+        // MPEG-2: 8 bits
+        let bytes = [0xFF, 0xF3, 0x90, 0x64, 0b1011_0010, 0b1000_0000];
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+        assert_eq!(frame.main_data_begin(), 0b1011_0010);
+    }
+
+    #[test]
+    fn test_verify_crc() {
+        // MPEG-1 Layer III joint stereo, CRC protected
+        let mut bytes = vec![0xFF, 0xFA, 0x90, 0x64, 0x60, 0x72];
+        bytes.extend(1..=32);
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+
+        assert_eq!(frame.data[0], 1);
+        assert!(frame.verify_crc(&bytes).unwrap());
+
+        // Corrupt a byte of the side info
+        bytes[10] ^= 0xFF;
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+        assert!(!frame.verify_crc(&bytes).unwrap());
+    }
+
+    #[test]
+    fn test_verify_crc_unprotected() {
+        let bytes = [0xFF, 0xFB, 0x90, 0x64];
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+
+        assert!(frame.verify_crc(&bytes).unwrap());
+    }
+
+    /// Packs `(value, bit count)` fields into bytes, most significant bit first
+    fn pack_bits(fields: &[(u32, u8)]) -> Vec<u8> {
+        let bits: Vec<u8> = fields
+            .iter()
+            .flat_map(|&(value, count)| (0..count).rev().map(move |bit| (value >> bit) as u8 & 1))
+            .collect();
+        bits.chunks(8)
+            .map(|byte| byte.iter().fold(0, |acc, bit| (acc << 1) | bit) << (8 - byte.len()))
+            .collect()
+    }
+
+    #[test]
+    fn test_side_info_mpeg1_mono() {
+        #[rustfmt::skip]
+        let granule_fields = [
+            // Granule 0: long blocks
+            (1000, 12), (200, 9), (150, 8), (9, 4), (0, 1),
+            (1, 5), (2, 5), (3, 5), (4, 4), (5, 3),
+            (1, 1), (0, 1), (1, 1),
+            // Granule 1: short blocks
+            (500, 12), (100, 9), (140, 8), (3, 4), (1, 1),
+            (2, 2), (0, 1), (7, 5), (8, 5), (1, 3), (2, 3), (3, 3),
+            (0, 1), (1, 1), (0, 1),
+        ];
+        let mut fields = vec![(300, 9), (0b10101, 5), (0b1001, 4)];
+        fields.extend_from_slice(&granule_fields);
+
+        let mut bytes = vec![0xFF, 0xFB, 0x90, 0xC4];
+        bytes.extend_from_slice(&pack_bits(&fields));
+        assert_eq!(bytes.len(), 4 + 17);
+
+        let side_info = MP3AudioFrame::from_bytes(&bytes)
+            .unwrap()
+            .side_info
+            .unwrap();
+
+        assert_eq!(side_info.main_data_begin, 300);
+        assert_eq!(side_info.private_bits, 0b10101);
+        assert_eq!(side_info.scfsi, vec![[true, false, false, true]]);
+        assert_eq!(side_info.granules.len(), 2);
+        assert_eq!(
+            side_info.granules[0][0],
+            GranuleInfo {
+                part2_3_length: 1000,
+                big_values: 200,
+                global_gain: 150,
+                scalefac_compress: 9,
+                window_switching_flag: false,
+                block_type: 0,
+                mixed_block_flag: false,
+                table_select: [1, 2, 3],
+                subblock_gain: [0, 0, 0],
+                region0_count: 4,
+                region1_count: 5,
+                preflag: true,
+                scalefac_scale: false,
+                count1table_select: true,
+            }
+        );
+        assert_eq!(
+            side_info.granules[1][0],
+            GranuleInfo {
+                part2_3_length: 500,
+                big_values: 100,
+                global_gain: 140,
+                scalefac_compress: 3,
+                window_switching_flag: true,
+                block_type: 2,
+                mixed_block_flag: false,
+                table_select: [7, 8, 0],
+                subblock_gain: [1, 2, 3],
+                region0_count: 8,
+                region1_count: 36,
+                preflag: false,
+                scalefac_scale: true,
+                count1table_select: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_side_info_mpeg2_stereo() {
+        #[rustfmt::skip]
+        let channel_fields = [
+            (2000, 12), (288, 9), (210, 8), (300, 9), (0, 1),
+            (15, 5), (16, 5), (17, 5), (6, 4), (2, 3),
+            (1, 1), (1, 1),
+        ];
+        let mut fields = vec![(200, 8), (0b11, 2)];
+        fields.extend_from_slice(&channel_fields);
+        fields.extend_from_slice(&channel_fields);
+
+        let mut bytes = vec![0xFF, 0xF3, 0x90, 0x04];
+        bytes.extend_from_slice(&pack_bits(&fields));
+        assert_eq!(bytes.len(), 4 + 17);
+
+        let side_info = MP3AudioFrame::from_bytes(&bytes)
+            .unwrap()
+            .side_info
+            .unwrap();
+
+        assert_eq!(side_info.main_data_begin, 200);
+        assert_eq!(side_info.private_bits, 0b11);
+        assert_eq!(side_info.scfsi, vec![[false; 4]; 2]);
+        assert_eq!(side_info.granules.len(), 1);
+        assert_eq!(side_info.granules[0].len(), 2);
+        assert_eq!(side_info.granules[0][0], side_info.granules[0][1]);
+        assert_eq!(side_info.granules[0][0].scalefac_compress, 300);
+        assert_eq!(side_info.granules[0][0].table_select, [15, 16, 17]);
+        assert!(!side_info.granules[0][0].preflag);
+        assert!(side_info.granules[0][0].count1table_select);
+    }
+
+    #[test]
+    fn test_side_info_truncated() {
+        let frame = MP3AudioFrame::from_bytes(&[0xFF, 0xFB, 0x90, 0x64, 0x00]).unwrap();
+        assert!(frame.side_info.is_none());
+    }
+
+    #[test]
+    fn test_sample_offset_of_frame() {
+        // An Info header with no optional fields, followed by a LAME tag with a delay of 576
+        let mut bytes = vec![0x00; 417];
+        bytes[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x64]);
+        bytes[36..44].copy_from_slice(b"Info\x00\x00\x00\x00");
+        bytes[44..53].copy_from_slice(b"LAME3.100");
+        bytes[65..68].copy_from_slice(&[0x24, 0x00, 0x00]);
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+
+        // The delay is trimmed from the start of frame 0
+        assert_eq!(frame.sample_offset_of_frame(0), 0);
+        assert_eq!(frame.sample_offset_of_frame(1), 1152 - 576);
+        assert_eq!(
+            frame.sample_offset_of_frame(2),
+            frame.sample_offset_of_frame(1) + 1152
+        );
+
+        // Without a LAME tag
+        let frame = MP3AudioFrame::from_bytes(&[0xFF, 0xFB, 0x90, 0x64]).unwrap();
+        assert_eq!(frame.sample_offset_of_frame(0), 0);
+        assert_eq!(frame.sample_offset_of_frame(1), 1152);
+    }
+
+    #[test]
+    fn test_free_format_frame_length() {
+        // MPEG-1 Layer III, free format, 44.1kHz. Each frame is 600 bytes
+        let header_bytes = [0xFF, 0xFB, 0x00, 0x64];
+        let mut stream = vec![0x00; 3 * 600];
+        for frame in stream.chunks_mut(600) {
+            frame[..4].copy_from_slice(&header_bytes);
+        }
+
+        let frames = crate::audio::parse_audio_frames(&stream).unwrap();
+
+        assert_eq!(frames.len(), 3);
+        for frame in &frames {
+            assert_eq!(frame.header.bitrate, None);
+            assert_eq!(frame.frame_length, 600);
+            assert_eq!(frame.bitrate(), 600 * 44100 / 144);
+        }
+    }
+
+    #[test]
+    fn test_free_format_frame_without_next_header() {
+        let header_bytes = [0xFF, 0xFB, 0x00, 0x64];
+
+        // The last frame of the stream runs to the end
+        let mut stream = vec![0x00; 600];
+        stream[..4].copy_from_slice(&header_bytes);
+        assert_eq!(
+            MP3AudioFrame::from_bytes(&stream).unwrap().frame_length,
+            600
+        );
+
+        // Junk that looks like a free-format header doesn't swallow the rest of the stream
+        let mut stream = vec![0x00; MAX_FREE_FORMAT_LENGTH + 100];
+        stream[..4].copy_from_slice(&header_bytes);
+        assert_eq!(
+            MP3AudioFrame::from_bytes(&stream).unwrap_err(),
+            DecodeError::UnterminatedFreeFormatFrame {
+                max_length: MAX_FREE_FORMAT_LENGTH
+            }
+        );
+        assert!(!crate::audio::is_frame_start(&stream, 0));
+    }
+
+    #[test]
+    fn test_free_format_bitrate_does_not_overflow() {
+        // MPEG-1 Layer III, free format, 48kHz
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFB, 0x04, 0x64]).unwrap();
+        let bitrate = MP3AudioFrame::compute_bitrate(&header, 100_000);
+
+        assert_eq!(bitrate, (100_000_u64 * 48_000 / 144) as u32);
+    }
+}
@@ -1,5 +1,4 @@
-use std::{io::{Error, ErrorKind}, usize};
-
+use crate::error::DecodeError;
 use crate::utils::HexSlice;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -15,24 +14,26 @@ impl MPEGVersion {
     /// Parses MPEG version from the 2-bit value in the frame header
     ///
     /// 00=MPEG-2.5, 01=reserved, 10=MPEG-2, 11=MPEG-1.
-    pub fn from_bits(bits: u8) -> Result<Self, Error> {
-        println!("Bits: {:02b}", bits);
+    pub fn from_bits(bits: u8) -> Result<Self, DecodeError> {
+        log::trace!("MPEG version bits: {bits:02b}");
         match bits {
             0b00 => Ok(Self::Mpeg2_5),
             0b10 => Ok(Self::Mpeg2),
             0b11 => Ok(Self::Mpeg1),
-            _ => Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("Expected 2-bit integer. Received {:08b}", bits),
-            )), // Shouldn't happen as we're working with 2 bits
+            0b01 => Err(DecodeError::ReservedMpegVersion),
+            _ => Err(DecodeError::InvalidFieldBits {
+                field: "MPEG version",
+                bits,
+            }), // Shouldn't happen as we're working with 2 bits
         }
     }
 
-    /// Returns the bitrate (in bps) given the layer_name and bitrate index
-    pub fn get_bitrate(&self, layer: Layer, index: u8) -> Result<Option<u32>, Error> {
+    /// Returns the bitrate (in bps) given the layer_name and bitrate index, or `None` for
+    /// free-format streams
+    pub fn get_bitrate(&self, layer: Layer, index: u8) -> Result<Option<u32>, DecodeError> {
         let table = match (self, layer) {
             (Self::Mpeg1, Layer::Layer1) => &[
-                32, 64, 36, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+                32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
             ],
             (Self::Mpeg1, Layer::Layer2) => &[
                 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
@@ -53,16 +54,16 @@ impl MPEGVersion {
             // bitrate index 0 is not accounted for in the table
             0b0001..=0b1110 => Ok(Some(table[(index as usize) - 1] * 1000)),
 
-            0b1111 => Ok(None), // Invalid. Should throw an error maybe?
-            _ => Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("Expected 4-bit index. Received {:08b}", index),
-            )),
+            0b1111 => Err(DecodeError::InvalidBitrateIndex(index)),
+            _ => Err(DecodeError::InvalidFieldBits {
+                field: "bitrate index",
+                bits: index,
+            }),
         }
     }
 
     /// Returns the sampling rate (in Hertz) given the sampling rate index
-    pub fn get_sampling_rate(&self, index: u8) -> Result<u16, Error> {
+    pub fn get_sampling_rate(&self, index: u8) -> Result<u16, DecodeError> {
         let table: &[u16; 3] = match self {
             Self::Mpeg1 => &[44100, 48000, 32000],
             Self::Mpeg2 => &[22050, 24000, 16000],
@@ -71,10 +72,7 @@ impl MPEGVersion {
 
         match index {
             0b00..=0b10 => Ok(table[index as usize]),
-            _ => Err(Error::new(
-                ErrorKind::InvalidData,
-                "Only valid integers, from 0 to 3 (inclusive), are allowed. Received {index}",
-            )),
+            _ => Err(DecodeError::InvalidSampleRateIndex(index)),
         }
     }
 }
@@ -86,15 +84,16 @@ pub enum Layer {
     Layer3,
 }
 impl Layer {
-    pub fn from_bits(bits: u8) -> Result<Self, Error> {
+    pub fn from_bits(bits: u8) -> Result<Self, DecodeError> {
         match bits {
             0b01 => Ok(Self::Layer3),
             0b10 => Ok(Self::Layer2),
             0b11 => Ok(Self::Layer1),
-            _ => Err(Error::new(
-                ErrorKind::InvalidData,
-                "Only valid integers, from 0 to 3 (inclusive), are allowed. Received {bits}",
-            )),
+            0b00 => Err(DecodeError::ReservedLayer),
+            _ => Err(DecodeError::InvalidFieldBits {
+                field: "layer",
+                bits,
+            }),
         }
     }
 
@@ -114,16 +113,24 @@ pub enum ChannelMode {
     DualChannel,
 }
 impl ChannelMode {
-    pub fn from_bits(bits: u8) -> Result<Self, Error> {
+    pub fn from_bits(bits: u8) -> Result<Self, DecodeError> {
         match bits {
             0b00 => Ok(Self::Stereo),
             0b01 => Ok(Self::JointStereo),
             0b10 => Ok(Self::DualChannel),
             0b11 => Ok(Self::SingleChannel),
-            _ => Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("Expected 2-bit number. Received {:08b}", bits),
-            )),
+            _ => Err(DecodeError::InvalidFieldBits {
+                field: "channel mode",
+                bits,
+            }),
+        }
+    }
+
+    /// Returns the number of channels, ie 1 for `SingleChannel` and 2 otherwise
+    pub fn channel_count(&self) -> u8 {
+        match self {
+            Self::SingleChannel => 1,
+            _ => 2,
         }
     }
 }
@@ -143,28 +150,69 @@ pub enum ModeExtension {
     Mode4,
 }
 impl ModeExtension {
-    pub fn from_bits(bits: u8) -> Result<Self, Error> {
+    pub fn from_bits(bits: u8) -> Result<Self, DecodeError> {
         match bits {
             0b00 => Ok(Self::Mode1),
             0b01 => Ok(Self::Mode2),
             0b10 => Ok(Self::Mode3),
             0b11 => Ok(Self::Mode4),
-            _ => Err(Error::new(
-                ErrorKind::InvalidData,
-                format!("Expected a 2-bit number. Received {:08b}", bits),
-            )),
+            _ => Err(DecodeError::InvalidFieldBits {
+                field: "mode extension",
+                bits,
+            }),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emphasis {
+    None,
+
+    // 50/15 ms
+    Ms5015,
+
+    // Not allowed by the spec. `from_bits` rejects it
+    Reserved,
+
+    // CCITT J.17
+    CcittJ17,
+}
+impl Emphasis {
+    pub fn from_bits(bits: u8) -> Result<Self, DecodeError> {
+        match bits {
+            0b00 => Ok(Self::None),
+            0b01 => Ok(Self::Ms5015),
+            0b11 => Ok(Self::CcittJ17),
+            0b10 => Err(DecodeError::ReservedEmphasis),
+            _ => Err(DecodeError::InvalidFieldBits {
+                field: "emphasis",
+                bits,
+            }),
+        }
+    }
+}
+
+/// Something about a header that is allowed by its syntax but not by the spec. These usually
+/// mean the header was misparsed, eg a false sync word in the middle of a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderWarning {
+    /// MPEG-1 Layer II only allows 32, 48, 56 and 80 kbps in mono, and only allows 224, 256, 320
+    /// and 384 kbps in the other channel modes
+    DisallowedBitrateForChannelMode {
+        bitrate: u32,
+        channel_mode: ChannelMode,
+    },
+}
+
 #[derive(Debug)]
 pub struct MP3AudioFrameHeader {
     mpeg_version: MPEGVersion,
     pub layer: Layer,
     has_crc: bool,
 
-    /// The bitrate in bps
-    pub bitrate: u32,
+    /// The bitrate in bps, or `None` for free-format streams, whose bitrate isn't signaled. See
+    /// `MP3AudioFrame::bitrate`
+    pub bitrate: Option<u32>,
 
     /// Sampling rate in Hertz
     pub sample_rate: u16,
@@ -174,34 +222,33 @@ pub struct MP3AudioFrameHeader {
 
     /// The state of the stereo intensity and mid-side (MS) stereo.
     ///
-    /// Only used when channel_mode is `ChannelMode::JointStereo`
-    mode_extension: ModeExtension,
+    /// `None` unless channel_mode is `ChannelMode::JointStereo`, as the bits are ignored otherwise
+    mode_extension: Option<ModeExtension>,
 
-    is_copywrighted: bool,
+    is_copyrighted: bool,
 
     /// Whether this bitstream is original or a copy
     is_original: bool,
 
-    // misc
+    /// The de-emphasis to apply on playback
+    emphasis: Emphasis,
+
+    /// The playback duration of this frame in seconds
     duration_per_frame: f64,
 }
 impl MP3AudioFrameHeader {
-    pub fn from_bytes(bytes: &[u8; 4]) -> Result<Self, Error> {
+    pub fn from_bytes(bytes: &[u8; 4]) -> Result<Self, DecodeError> {
         let data = u32::from_be_bytes(*bytes);
-        println!("{data}: {}", HexSlice::new(bytes));
+        log::trace!("Frame header: {}", HexSlice::new(bytes));
         let mut bit_position = 32;
 
         bit_position -= 11; // First 11 bits (Supports MPEG2.5)
         let sync_word_bits = data >> bit_position;
         let sync_word_valid = 0x7FF;
         if sync_word_bits & sync_word_valid != sync_word_valid {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                format!(
-                    "Expected 0xFFF for sync word. Received 0x{:X}",
-                    sync_word_bits
-                ),
-            ));
+            return Err(DecodeError::InvalidSyncWord {
+                found: sync_word_bits as u16,
+            });
         }
 
         bit_position -= 2; // Next 2 bits
@@ -212,12 +259,20 @@ impl MP3AudioFrameHeader {
         let layer_bits = ((data >> bit_position) & 0b11) as u8;
         let layer = Layer::from_bits(layer_bits)?;
 
+        // MPEG-2.5 is an extension of MPEG-2 for low sampling rates, and only defines Layer III
+        if mpeg_version == MPEGVersion::Mpeg2_5 && layer != Layer::Layer3 {
+            return Err(DecodeError::InvalidVersionLayer {
+                version: mpeg_version,
+                layer,
+            });
+        }
+
         bit_position -= 1; // Next bit
         let has_crc = ((data >> bit_position) & 0b1) == 0;
 
         bit_position -= 4; // Next 4 bits
         let bitrate_index = ((data >> bit_position) & 0b1111) as u8;
-        let bitrate_from_index = mpeg_version.get_bitrate(layer, bitrate_index)?;
+        let bitrate = mpeg_version.get_bitrate(layer, bitrate_index)?;
 
         bit_position -= 2; // Next 2 bits
         let sampling_rate_index = ((data >> bit_position) & 0b11) as u8;
@@ -236,17 +291,21 @@ impl MP3AudioFrameHeader {
 
         bit_position -= 2; // Next 2 bits;
         let mode_extension_bits = ((data >> bit_position) & 0b11) as u8;
-        let mode_extension = ModeExtension::from_bits(mode_extension_bits)?;
+        let mode_extension = match channel_mode {
+            ChannelMode::JointStereo => Some(ModeExtension::from_bits(mode_extension_bits)?),
+            _ => None,
+        };
 
         bit_position -= 1; // Next bit
-        let is_copywrighted = ((data >> bit_position) & 0b1) == 1;
+        let is_copyrighted = ((data >> bit_position) & 0b1) == 1;
         bit_position -= 1; // Next bit
         let is_original = ((data >> bit_position) & 0b1) == 1;
 
-        // Ignore the emphasis
+        bit_position -= 2; // Last 2 bits
+        let emphasis_bits = ((data >> bit_position) & 0b11) as u8;
+        let emphasis = Emphasis::from_bits(emphasis_bits)?;
 
-        let bitrate = bitrate_from_index.unwrap();
-        let duration_per_frame = layer.get_samples_per_frame() as f64 / sample_rate as f64;
+        let duration_per_frame = samples_per_frame(mpeg_version, layer) as f64 / sample_rate as f64;
 
         Ok(Self {
             mpeg_version,
@@ -257,11 +316,123 @@ impl MP3AudioFrameHeader {
             has_padding,
             channel_mode,
             mode_extension,
-            is_copywrighted,
+            is_copyrighted,
             is_original,
+            emphasis,
             duration_per_frame,
         })
     }
+
+    pub fn mpeg_version(&self) -> MPEGVersion {
+        self.mpeg_version
+    }
+
+    pub fn channel_mode(&self) -> ChannelMode {
+        self.channel_mode
+    }
+
+    /// The stereo mode of a joint stereo frame, or `None` for the other channel modes
+    pub fn mode_extension(&self) -> Option<ModeExtension> {
+        self.mode_extension
+    }
+
+    /// Returns the number of samples (per channel) this frame decodes to
+    pub fn samples_per_frame(&self) -> u16 {
+        samples_per_frame(self.mpeg_version, self.layer)
+    }
+
+    /// The de-emphasis to apply on playback
+    pub fn emphasis(&self) -> Emphasis {
+        self.emphasis
+    }
+
+    /// The playback duration of this frame in seconds
+    pub fn duration(&self) -> f64 {
+        self.duration_per_frame
+    }
+
+    pub fn is_copyrighted(&self) -> bool {
+        self.is_copyrighted
+    }
+
+    /// Whether this bitstream is original or a copy
+    pub fn is_original(&self) -> bool {
+        self.is_original
+    }
+
+    /// Whether a CRC follows the header
+    pub fn has_crc(&self) -> bool {
+        self.has_crc
+    }
+
+    /// Checks the header for combinations of fields that are disallowed by the spec
+    pub fn warnings(&self) -> Vec<HeaderWarning> {
+        let mut warnings = Vec::new();
+
+        if (self.mpeg_version, self.layer) == (MPEGVersion::Mpeg1, Layer::Layer2)
+            && let Some(bitrate) = self.bitrate
+        {
+            let is_mono = self.channel_mode == ChannelMode::SingleChannel;
+            let is_allowed = match bitrate {
+                32_000 | 48_000 | 56_000 | 80_000 => is_mono,
+                224_000 | 256_000 | 320_000 | 384_000 => !is_mono,
+                _ => true,
+            };
+
+            if !is_allowed {
+                warnings.push(HeaderWarning::DisallowedBitrateForChannelMode {
+                    bitrate,
+                    channel_mode: self.channel_mode,
+                });
+            }
+        }
+
+        warnings
+    }
+
+    /// Returns the number of granules in this frame
+    ///
+    /// MPEG-1 Layer III frames hold 2 granules, MPEG-2/2.5 Layer III frames hold 1.
+    /// Layer I and II frames aren't split into granules so they count as 1.
+    pub fn granule_count(&self) -> u8 {
+        match (self.mpeg_version, self.layer) {
+            (MPEGVersion::Mpeg1, Layer::Layer3) => 2,
+            (_, _) => 1,
+        }
+    }
+
+    /// Returns the size (in bytes) of the Layer III side information that follows the header
+    /// (and CRC, if present). Layer I and II frames have no side information.
+    pub fn side_info_length(&self) -> usize {
+        if self.layer != Layer::Layer3 {
+            return 0;
+        }
+
+        match (self.mpeg_version, self.channel_mode) {
+            (MPEGVersion::Mpeg1, ChannelMode::SingleChannel) => 17,
+            (MPEGVersion::Mpeg1, _) => 32,
+            (_, ChannelMode::SingleChannel) => 9,
+            (_, _) => 17,
+        }
+    }
+
+    /// Returns the size (in bytes) of the CRC that follows the header
+    pub fn crc_length(&self) -> usize {
+        match self.has_crc {
+            true => 2,
+            false => 0,
+        }
+    }
+}
+
+fn samples_per_frame(mpeg_version: MPEGVersion, layer: Layer) -> u16 {
+    match (mpeg_version, layer) {
+        (MPEGVersion::Mpeg1, _) | (_, Layer::Layer1 | Layer::Layer2) => {
+            layer.get_samples_per_frame()
+        }
+        // MPEG-2/2.5 Layer III frames hold a single granule
+        (_, Layer::Layer3) => 576,
+    }
 }
 
 #[cfg(test)]
@@ -274,15 +445,16 @@ mod tests {
         let header_bytes = [0xFF, 0xFB, 0x90, 0x44];
         let header = MP3AudioFrameHeader::from_bytes(&header_bytes).unwrap();
 
-        assert_eq!(header.mpeg_version, MPEGVersion::Mpeg1);
+        assert_eq!(header.mpeg_version(), MPEGVersion::Mpeg1);
         assert_eq!(header.layer, Layer::Layer3);
-        assert_eq!(header.has_crc, false);
-        assert_eq!(header.bitrate, 128);
+        assert!(!header.has_crc());
+        assert_eq!(header.bitrate, Some(128_000));
         assert_eq!(header.sample_rate, 44100);
-        assert_eq!(header.has_padding, false);
-        assert_eq!(header.channel_mode, ChannelMode::JointStereo);
-        assert_eq!(header.is_copywrighted, false);
-        assert_eq!(header.is_original, true);
+        assert!(!header.has_padding);
+        assert_eq!(header.channel_mode(), ChannelMode::JointStereo);
+        assert!(!header.is_copyrighted());
+        assert!(header.is_original());
+        assert_eq!(header.emphasis(), Emphasis::None);
     }
 
     #[test]
@@ -291,22 +463,158 @@ mod tests {
         let header_bytes = [0xFF, 0xFB, 0x90, 0x64];
         let header = MP3AudioFrameHeader::from_bytes(&header_bytes).unwrap();
 
-        assert_eq!(header.mpeg_version, MPEGVersion::Mpeg1);
+        assert_eq!(header.mpeg_version(), MPEGVersion::Mpeg1);
         assert_eq!(header.layer, Layer::Layer3);
-        assert_eq!(header.has_crc, false);
-        assert_eq!(header.bitrate, 128);
+        assert!(!header.has_crc());
+        assert_eq!(header.bitrate, Some(128_000));
         assert_eq!(header.sample_rate, 44100);
-        assert_eq!(header.has_padding, false);
-        assert_eq!(header.channel_mode, ChannelMode::JointStereo);
-        assert_eq!(header.is_copywrighted, false);
-        assert_eq!(header.is_original, true);
+        assert!(!header.has_padding);
+        assert_eq!(header.channel_mode(), ChannelMode::JointStereo);
+        assert!(!header.is_copyrighted());
+        assert!(header.is_original());
+        assert_eq!(header.emphasis(), Emphasis::None);
+    }
+
+    #[test]
+    fn test_emphasis() {
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFB, 0x90, 0x65]).unwrap();
+        assert_eq!(header.emphasis(), Emphasis::Ms5015);
+
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFB, 0x90, 0x67]).unwrap();
+        assert_eq!(header.emphasis(), Emphasis::CcittJ17);
+    }
+
+    #[test]
+    fn test_reserved_emphasis() {
+        let result = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFB, 0x90, 0x66]);
+        assert_eq!(result.unwrap_err(), DecodeError::ReservedEmphasis);
+    }
+
+    #[test]
+    fn test_layer2_bitrate_channel_mode_warnings() {
+        // MPEG-1 Layer II, 32 kbps, stereo
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFD, 0x10, 0x04]).unwrap();
+        assert_eq!(
+            header.warnings(),
+            vec![HeaderWarning::DisallowedBitrateForChannelMode {
+                bitrate: 32_000,
+                channel_mode: ChannelMode::Stereo,
+            }]
+        );
+
+        // MPEG-1 Layer II, 384 kbps, mono
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFD, 0xE0, 0xC4]).unwrap();
+        assert_eq!(header.warnings().len(), 1);
+
+        // MPEG-1 Layer II, 32 kbps, mono
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFD, 0x10, 0xC4]).unwrap();
+        assert!(header.warnings().is_empty());
+
+        // Layer III has no such restriction
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFB, 0x10, 0x04]).unwrap();
+        assert!(header.warnings().is_empty());
     }
 
     #[test]
     fn test_invalid_sync_word() {
         let header_bytes = [0x00, 0x00, 0x00, 0x00];
         let result = MP3AudioFrameHeader::from_bytes(&header_bytes);
-        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err(),
+            DecodeError::InvalidSyncWord { found: 0 }
+        );
+    }
+
+    #[test]
+    fn test_reserved_fields() {
+        let result = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xEB, 0x90, 0x64]);
+        assert_eq!(result.unwrap_err(), DecodeError::ReservedMpegVersion);
+
+        let result = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xF9, 0x90, 0x64]);
+        assert_eq!(result.unwrap_err(), DecodeError::ReservedLayer);
+
+        let result = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFB, 0xF0, 0x64]);
+        assert_eq!(
+            result.unwrap_err(),
+            DecodeError::InvalidBitrateIndex(0b1111)
+        );
+    }
+
+    #[test]
+    fn test_invalid_version_layer() {
+        // MPEG-2.5 Layer I
+        let result = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xE7, 0x90, 0x64]);
+        assert_eq!(
+            result.unwrap_err(),
+            DecodeError::InvalidVersionLayer {
+                version: MPEGVersion::Mpeg2_5,
+                layer: Layer::Layer1,
+            }
+        );
+
+        // MPEG-2.5 Layer II
+        let result = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xE5, 0x90, 0x64]);
+        assert_eq!(
+            result.unwrap_err(),
+            DecodeError::InvalidVersionLayer {
+                version: MPEGVersion::Mpeg2_5,
+                layer: Layer::Layer2,
+            }
+        );
+
+        // MPEG-2.5 Layer III
+        assert!(MP3AudioFrameHeader::from_bytes(&[0xFF, 0xE3, 0x90, 0x64]).is_ok());
+    }
+
+    #[test]
+    fn test_mpeg1_layer1_bitrates() {
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFF, 0x30, 0x00]).unwrap();
+        assert_eq!(header.bitrate, Some(96_000));
+    }
+
+    #[test]
+    fn test_side_info_length() {
+        // MPEG-1 Layer III, joint stereo
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFB, 0x90, 0x64]).unwrap();
+        assert_eq!(header.side_info_length(), 32);
+        assert_eq!(header.crc_length(), 0);
+
+        // MPEG-1 Layer III, mono, CRC protected
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFA, 0x90, 0xC4]).unwrap();
+        assert_eq!(header.side_info_length(), 17);
+        assert_eq!(header.crc_length(), 2);
     }
-}
 
+    #[test]
+    fn test_granule_count() {
+        // MPEG-1 Layer III
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFB, 0x90, 0x64]).unwrap();
+        assert_eq!(header.granule_count(), 2);
+
+        // MPEG-2 Layer III
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xF3, 0x90, 0x64]).unwrap();
+        assert_eq!(header.granule_count(), 1);
+
+        // MPEG-2.5 Layer III
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xE3, 0x90, 0x64]).unwrap();
+        assert_eq!(header.granule_count(), 1);
+
+        // MPEG-1 Layer I
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFF, 0x90, 0x64]).unwrap();
+        assert_eq!(header.granule_count(), 1);
+    }
+
+    #[test]
+    fn test_mode_extension_only_for_joint_stereo() {
+        // Joint stereo, mode extension 0b10
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFB, 0x90, 0x64]).unwrap();
+        assert_eq!(header.channel_mode().channel_count(), 2);
+        assert_eq!(header.mode_extension(), Some(ModeExtension::Mode3));
+
+        // Mono, with the (ignored) mode extension bits set
+        let header = MP3AudioFrameHeader::from_bytes(&[0xFF, 0xFB, 0x90, 0xF4]).unwrap();
+        assert_eq!(header.channel_mode(), ChannelMode::SingleChannel);
+        assert_eq!(header.channel_mode().channel_count(), 1);
+        assert_eq!(header.mode_extension(), None);
+    }
+}
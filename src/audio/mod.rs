@@ -1,31 +1,351 @@
-mod frame;
-mod header;
+pub mod frame;
+pub mod header;
+pub mod parser;
+pub mod reader;
+pub mod scan;
+pub mod vbr;
 
 use self::frame::MP3AudioFrame;
-use std::io::Error;
+use self::header::MP3AudioFrameHeader;
+use self::parser::FrameParser;
+use crate::error::DecodeError;
+use crate::metadata::trailing::trailing_tags_start;
+use std::time::Duration;
 
-pub fn parse_audio_frames(bytes: &[u8]) -> Result<Vec<MP3AudioFrame>, Error> {
+/// The default number of junk bytes skipped while looking for the next frame
+pub const DEFAULT_MAX_SKIP: usize = 64 * 1024;
+
+/// Parses every audio frame in `bytes` (see `FrameParser`)
+///
+/// A last frame cut short by the end of `bytes` is still returned, unlike with `scan::scan`
+pub fn parse_audio_frames(bytes: &[u8]) -> Result<Vec<MP3AudioFrame<'_>>, DecodeError> {
+    FrameParser::new(bytes).collect()
+}
+
+/// Parses audio frames, skipping at most `max_skip` junk bytes between two frames
+///
+/// Junk that runs to the end of `bytes` (eg an ID3v1 tag) is always ignored
+pub fn parse_audio_frames_with_max_skip(
+    bytes: &[u8],
+    max_skip: usize,
+) -> Result<Vec<MP3AudioFrame<'_>>, DecodeError> {
+    FrameParser::new(bytes).with_max_skip(max_skip).collect()
+}
+
+/// Parses audio frames starting at `start` rather than the begining of `bytes`
+///
+/// Useful when the start of the audio is already known, eg from a separate tag parser
+pub fn parse_audio_frames_at(
+    bytes: &[u8],
+    start: usize,
+) -> Result<Vec<MP3AudioFrame<'_>>, DecodeError> {
+    if start > bytes.len() {
+        return Err(DecodeError::InvalidStartOffset {
+            offset: start,
+            len: bytes.len(),
+        });
+    }
+
+    // Seek rather than slicing, so the frames' offsets are relative to `bytes`
+    let mut parser = FrameParser::new(bytes);
+    parser.seek(start)?;
+    parser.collect()
+}
+
+/// Parses audio frames from a buffer that may begin partway through a frame, eg a byte range
+/// captured from the middle of a stream
+///
+/// The leading partial frame is discarded, and the number of bytes discarded is returned
+/// alongside the frames
+pub fn parse_audio_frames_from_partial(
+    bytes: &[u8],
+) -> Result<(usize, Vec<MP3AudioFrame<'_>>), DecodeError> {
+    let discarded = (0..bytes.len())
+        .find(|&index| is_frame_start(bytes, index))
+        .unwrap_or(bytes.len());
+    let mut parser = FrameParser::new(bytes);
+    parser.seek(discarded)?;
+    let frames = parser.collect::<Result<_, _>>()?;
+
+    Ok((discarded, frames))
+}
+
+/// Returns the offset just past the last complete, valid frame
+///
+/// `bytes` is expected to begin at the first audio frame. Junk between frames is skipped like
+/// `FrameParser` does, and the search stops at any ID3v1, APE or Lyrics3 tag after the audio,
+/// so the audio occupies `bytes[..end]`.
+pub fn find_audio_end(bytes: &[u8]) -> usize {
+    let audio = &bytes[..trailing_tags_start(bytes)];
+
+    FrameParser::new(audio)
+        .map_while(Result::ok)
+        .map(|frame| frame.byte_range().end)
+        .take_while(|&end| end <= audio.len())
+        .last()
+        .unwrap_or(0)
+}
+
+/// Estimates an upper bound on the size (in bytes) of the 16-bit PCM that `bytes` would decode
+/// to, cheaply enough to enforce an output size limit before decoding
+///
+/// The frame count is taken from the first frame's Xing/Info or VBRI header, if it has one.
+/// Otherwise every frame is assumed to be as short as the first frame's version and layer allow,
+/// so a long first frame followed by short ones can't decode to more than the estimate. The
+/// first frame's channel mode is assumed throughout.
+pub fn estimate_decoded_size(bytes: &[u8]) -> Result<u64, DecodeError> {
+    let frame = MP3AudioFrame::from_bytes(bytes)?;
+    let header = &frame.header;
+
+    let frame_count = match frame.vbr_header().and_then(|vbr| vbr.frame_count) {
+        Some(frame_count) => frame_count as u64,
+        None => (bytes.len() as u64).div_ceil(MP3AudioFrame::min_frame_length(header) as u64),
+    };
+    let bytes_per_frame =
+        header.samples_per_frame() as u64 * header.channel_mode().channel_count() as u64 * 2;
+
+    Ok(frame_count * bytes_per_frame)
+}
+
+/// Parses audio frames until `max_duration` worth of audio has been collected
+///
+/// Useful for indexing only the begining of a file, eg the first minute
+pub fn parse_audio_frames_for(
+    bytes: &[u8],
+    max_duration: Duration,
+) -> Result<Vec<MP3AudioFrame<'_>>, DecodeError> {
+    let mut parser = FrameParser::new(bytes);
+    let mut frames = Vec::new();
+    let mut elapsed = 0.0;
+
+    while elapsed < max_duration.as_secs_f64() {
+        let Some(frame) = parser.next() else {
+            break;
+        };
+
+        let frame = frame?;
+        elapsed += frame.header.duration();
+        frames.push(frame);
+    }
+
+    Ok(frames)
+}
+
+/// Returns the total playback duration of `frames`
+///
+/// If the first frame holds a Xing/Info or VBRI header with a frame count, the count is used
+/// instead of summing each frame, since the header describes the whole stream (not just `frames`)
+pub fn total_duration(frames: &[MP3AudioFrame]) -> Duration {
+    if let Some(duration) = frames.first().and_then(vbr_duration) {
+        return duration;
+    }
+
+    Duration::from_secs_f64(
+        frames
+            .iter()
+            .map(|frame| frame.header.duration())
+            .sum::<f64>(),
+    )
+}
+
+/// Returns the total playback duration of the audio frames in `bytes`
+///
+/// Like `total_duration`, but parses the frames lazily instead of collecting them
+pub fn stream_duration(bytes: &[u8]) -> Result<Duration, DecodeError> {
+    let mut parser = FrameParser::new(bytes);
+    let Some(first_frame) = parser.next().transpose()? else {
+        return Ok(Duration::ZERO);
+    };
+
+    if let Some(duration) = vbr_duration(&first_frame) {
+        return Ok(duration);
+    }
+
+    let mut seconds = first_frame.header.duration();
+    for frame in parser {
+        seconds += frame?.header.duration();
+    }
+
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+/// Returns the duration given by the frame count of `frame`'s VBR header, if it has one
+fn vbr_duration(frame: &MP3AudioFrame) -> Option<Duration> {
+    let frame_count = frame.vbr_header()?.frame_count?;
+    Some(Duration::from_secs_f64(
+        frame_count as f64 * frame.header.duration(),
+    ))
+}
+
+/// Returns the bytes of the frames covering `start..end`, without decoding them
+///
+/// The range is widened to frame boundaries. For Layer III, the frames whose main data the first
+/// frame's bit reservoir reaches into are included too, so the extracted audio decodes on its own.
+/// Returns an empty buffer if no frame overlaps the range.
+///
+/// `bytes` is expected to begin at the first audio frame
+pub fn extract_audio_range(
+    bytes: &[u8],
+    start: Duration,
+    end: Duration,
+) -> Result<Vec<u8>, DecodeError> {
+    let (start, end) = (start.as_secs_f64(), end.as_secs_f64());
+
+    let mut parser = FrameParser::new(bytes);
     let mut frames = Vec::new();
+    let mut offset = 0;
+    let mut elapsed = 0.0;
+    let mut first_index = None;
+
+    while elapsed < end {
+        let Some(frame) = parser.next() else {
+            break;
+        };
+
+        let frame = frame?;
+        let frame_end = elapsed + frame.header.duration();
+        if first_index.is_none() && frame_end > start {
+            first_index = Some(frames.len());
+        }
+
+        offset = frame.byte_range().end;
+        frames.push(frame);
+        elapsed = frame_end;
+    }
+
+    let Some(mut first_index) = first_index else {
+        return Ok(Vec::new());
+    };
+
+    // Walk back until the previous frames hold enough main data for the first frame's reservoir
+    let mut reservoir_needed = frames[first_index].main_data_begin() as usize;
+    while reservoir_needed > 0 && first_index > 0 {
+        first_index -= 1;
+        reservoir_needed = reservoir_needed.saturating_sub(frames[first_index].main_data().len());
+    }
+
+    let range_start = frames[first_index].offset;
+    Ok(bytes
+        .get(range_start..offset)
+        .ok_or(DecodeError::TruncatedFrame {
+            needed: offset - range_start,
+            got: bytes.len() - range_start,
+        })?
+        .to_vec())
+}
+
+/// How far (in bytes) either side of a frame's computed end to look for the next sync word
+const MAX_SYNC_DRIFT: usize = 8;
+
+/// A frame whose computed length didn't land on the next frame's sync word
+#[derive(Debug, PartialEq, Eq)]
+pub struct FrameLengthDiscrepancy {
+    /// Offset of the frame whose length was off
+    pub offset: usize,
+
+    /// The length computed from the frame's header
+    pub frame_length: u32,
+
+    /// How many bytes after (positive) or before (negative) the computed end the next frame
+    /// actually begins
+    pub drift: isize,
+}
+
+/// Parses audio frames, checking that each frame's computed length lands on the next sync word
+///
+/// When it doesn't, the nearest sync word within `MAX_SYNC_DRIFT` bytes of the computed end is
+/// used instead and the discrepancy is recorded. Useful for ABR/edited files whose frame
+/// lengths are slightly off.
+pub fn parse_audio_frames_verified(
+    bytes: &[u8],
+) -> Result<(Vec<MP3AudioFrame<'_>>, Vec<FrameLengthDiscrepancy>), DecodeError> {
+    let mut frames = Vec::new();
+    let mut discrepancies = Vec::new();
     let mut current_index = 0;
 
     while current_index < bytes.len() {
-        let frame = MP3AudioFrame::from_bytes(&bytes[current_index..])?;
-        //println!("{current_index} {} {}", bytes.len(), frame.size);
-        current_index += frame.frame_length as usize;
+        let mut frame = MP3AudioFrame::from_bytes(&bytes[current_index..])?;
+        frame.offset = current_index;
+        let expected_end = current_index + frame.frame_length as usize;
+
+        let mut next_index = expected_end;
+        if expected_end < bytes.len() && !has_sync_word(bytes, expected_end) {
+            let resynced = (1..=MAX_SYNC_DRIFT as isize)
+                .flat_map(|d| [d, -d])
+                .find_map(|drift| {
+                    let candidate = expected_end.checked_add_signed(drift)?;
+                    (candidate > current_index + 4 && has_sync_word(bytes, candidate))
+                        .then_some((candidate, drift))
+                });
+
+            if let Some((candidate, drift)) = resynced {
+                next_index = candidate;
+                discrepancies.push(FrameLengthDiscrepancy {
+                    offset: current_index,
+                    frame_length: frame.frame_length,
+                    drift,
+                });
+            }
+        }
 
+        current_index = next_index;
         frames.push(frame);
+    }
 
-        if current_index > 1000 {
-            break;
+    Ok((frames, discrepancies))
+}
+
+/// Whether a valid frame header begins at `index`
+fn has_sync_word(bytes: &[u8], index: usize) -> bool {
+    bytes
+        .get(index..index + 4)
+        .is_some_and(|header| MP3AudioFrameHeader::from_bytes(header.try_into().unwrap()).is_ok())
+}
+
+/// Whether a complete frame begins at `index`
+///
+/// The frame must be followed by another sync word (or the end of `bytes`), which filters out
+/// `0xFF` bytes that happen to appear in junk or in the middle of a frame.
+pub(crate) fn is_frame_start(bytes: &[u8], index: usize) -> bool {
+    if !has_sync_word(bytes, index) {
+        return false;
+    }
+
+    let Ok(frame) = MP3AudioFrame::from_bytes(&bytes[index..]) else {
+        return false;
+    };
+    let next_index = index + frame.frame_length as usize;
+    next_index == bytes.len() || has_sync_word(bytes, next_index)
+}
+
+/// Scans forward from `start` for the next frame, skipping at most `max_skip` junk bytes
+///
+/// Returns `None` if the end of `bytes` is reached first.
+pub(crate) fn find_next_frame(
+    bytes: &[u8],
+    start: usize,
+    max_skip: usize,
+) -> Result<Option<usize>, DecodeError> {
+    for index in start..bytes.len() {
+        if index - start > max_skip {
+            return Err(DecodeError::NoFrameFound {
+                offset: start,
+                max_skip,
+            });
+        }
+
+        if is_frame_start(bytes, index) {
+            return Ok(Some(index));
         }
     }
 
-    Ok(frames)
+    Ok(None)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::test_support::build_stream;
 
     #[test]
     fn test_parse_audio_frames() {
@@ -36,4 +356,265 @@ mod test {
         assert_eq!(frames.len(), 1);
         // assert_eq!(frames[0].frame_length, 4);
     }
+
+    #[test]
+    fn test_parse_audio_frames_for_duration() {
+        // MPEG-1 Layer III, 128kbps, 44.1kHz
+        let stream = build_stream([0xFF, 0xFB, 0x90, 0x64], 50);
+        let frames = parse_audio_frames_for(&stream, Duration::from_secs_f64(1.0)).unwrap();
+
+        // Each frame holds 1152 samples, so 1s of 44.1kHz audio spans 39 frames
+        let expected = (44100.0_f64 / 1152.0).ceil() as usize;
+        assert_eq!(frames.len(), expected);
+    }
+
+    #[test]
+    fn test_parse_audio_frames_at() {
+        let stream = build_stream([0xFF, 0xFB, 0x90, 0x64], 5);
+        let frames = parse_audio_frames(&stream).unwrap();
+
+        let start = (frames[0].frame_length + frames[1].frame_length) as usize;
+        let tail = parse_audio_frames_at(&stream, start).unwrap();
+
+        assert_eq!(tail.len(), frames.len() - 2);
+        for (frame, expected) in tail.iter().zip(&frames[2..]) {
+            assert_eq!(frame.frame_length, expected.frame_length);
+            assert_eq!(frame.offset, expected.offset);
+            assert!(std::ptr::eq(frame.data, expected.data));
+        }
+    }
+
+    #[test]
+    fn test_parse_audio_frames_at_out_of_bounds() {
+        let stream = build_stream([0xFF, 0xFB, 0x90, 0x64], 1);
+        let result = parse_audio_frames_at(&stream, stream.len() + 1);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_find_audio_end_excludes_id3v1() {
+        let mut stream = build_stream([0xFF, 0xFB, 0x90, 0x64], 3);
+        let audio_length = stream.len();
+
+        let mut id3v1 = vec![0; 128];
+        id3v1[..3].copy_from_slice(b"TAG");
+        stream.extend_from_slice(&id3v1);
+
+        assert_eq!(find_audio_end(&stream), audio_length);
+    }
+
+    #[test]
+    fn test_find_audio_end_skips_junk() {
+        let header_bytes = [0xFF, 0xFB, 0x90, 0x64];
+        let mut stream = build_stream(header_bytes, 2);
+        stream.extend_from_slice(&[0x12, 0xFF, 0x00, 0x34]);
+        stream.extend_from_slice(&build_stream(header_bytes, 2));
+        let audio_length = stream.len();
+
+        let mut id3v1 = vec![0; 128];
+        id3v1[..3].copy_from_slice(b"TAG");
+        stream.extend_from_slice(&id3v1);
+
+        assert_eq!(find_audio_end(&stream), audio_length);
+    }
+
+    #[test]
+    fn test_estimate_decoded_size() {
+        // Joint stereo, 1152 samples per frame. The shortest MPEG-1 Layer III frame (32kbps at
+        // 48kHz) is 96 bytes
+        let stream = build_stream([0xFF, 0xFB, 0x90, 0x64], 10);
+        let estimate = estimate_decoded_size(&stream).unwrap();
+
+        assert_eq!(estimate, (stream.len() as u64).div_ceil(96) * 1152 * 2 * 2);
+
+        // MPEG-2 Layer III frames hold a single granule, ie 576 samples. The shortest (8kbps at
+        // 24kHz) is 24 bytes
+        let stream = build_stream([0xFF, 0xF3, 0x90, 0x64], 10);
+        let estimate = estimate_decoded_size(&stream).unwrap();
+
+        assert_eq!(estimate, (stream.len() as u64).div_ceil(24) * 576 * 2 * 2);
+    }
+
+    #[test]
+    fn test_estimate_decoded_size_is_upper_bound() {
+        // A 320kbps first frame, followed by 32kbps frames
+        let mut stream = build_stream([0xFF, 0xFB, 0xE0, 0x64], 1);
+        stream.extend_from_slice(&build_stream([0xFF, 0xFB, 0x14, 0x64], 100));
+        let frame_count = parse_audio_frames(&stream).unwrap().len() as u64;
+
+        assert_eq!(frame_count, 101);
+        assert!(estimate_decoded_size(&stream).unwrap() >= frame_count * 1152 * 2 * 2);
+    }
+
+    #[test]
+    fn test_estimate_decoded_size_uses_xing_frame_count() {
+        let mut stream = build_stream([0xFF, 0xFB, 0x90, 0x64], 3);
+
+        // Xing header after the 32 bytes of side info, claiming 1000 frames
+        stream[36..40].copy_from_slice(b"Xing");
+        stream[40..44].copy_from_slice(&0x1_u32.to_be_bytes());
+        stream[44..48].copy_from_slice(&1000_u32.to_be_bytes());
+
+        assert_eq!(estimate_decoded_size(&stream).unwrap(), 1000 * 1152 * 2 * 2);
+    }
+
+    #[test]
+    fn test_parse_audio_frames_verified_resyncs_drift() {
+        let header_bytes = [0xFF, 0xFB, 0x90, 0x64];
+        let frame = build_stream(header_bytes, 1);
+
+        // The first frame is two bytes longer than its header says
+        let mut stream = frame.clone();
+        stream.extend_from_slice(&[0x00, 0x00]);
+        stream.extend_from_slice(&build_stream(header_bytes, 2));
+
+        let (frames, discrepancies) = parse_audio_frames_verified(&stream).unwrap();
+
+        assert_eq!(frames.len(), 3);
+        assert_eq!(
+            frames.iter().map(|frame| frame.offset).collect::<Vec<_>>(),
+            [0, frame.len() + 2, 2 * frame.len() + 2]
+        );
+        assert_eq!(
+            discrepancies,
+            vec![FrameLengthDiscrepancy {
+                offset: 0,
+                frame_length: frame.len() as u32,
+                drift: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_audio_frames_resyncs_past_junk() {
+        let header_bytes = [0xFF, 0xFB, 0x90, 0x64];
+
+        let mut stream = build_stream(header_bytes, 2);
+        // Junk, including a false sync word
+        stream.extend_from_slice(&[0x12, 0xFF, 0xFB, 0x34, 0x56, 0x00, 0xFF]);
+        stream.extend_from_slice(&build_stream(header_bytes, 2));
+        // Trailing junk, eg an ID3v1 tag
+        stream.extend_from_slice(b"TAG");
+        stream.extend_from_slice(&[0x00; 125]);
+
+        let frames = parse_audio_frames(&stream).unwrap();
+        assert_eq!(frames.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_audio_frames_max_skip() {
+        let header_bytes = [0xFF, 0xFB, 0x90, 0x64];
+
+        let mut stream = build_stream(header_bytes, 1);
+        stream.extend_from_slice(&[0x00; 100]);
+        stream.extend_from_slice(&build_stream(header_bytes, 1));
+
+        assert_eq!(
+            parse_audio_frames_with_max_skip(&stream, 100)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(parse_audio_frames_with_max_skip(&stream, 99).is_err());
+    }
+
+    #[test]
+    fn test_parse_audio_frames_from_partial() {
+        let header_bytes = [0xFF, 0xFB, 0x90, 0x64];
+
+        // Start 100 bytes into the first frame, which happens to contain a false sync word
+        let mut stream = build_stream(header_bytes, 4);
+        stream[150..154].copy_from_slice(&header_bytes);
+        let partial = &stream[100..];
+
+        let (discarded, frames) = parse_audio_frames_from_partial(partial).unwrap();
+
+        assert_eq!(discarded, 417 - 100);
+        assert_eq!(frames.len(), 3);
+    }
+
+    #[test]
+    fn test_total_duration() {
+        // 38 frames of 1152 samples at 44.1kHz
+        let stream = build_stream([0xFF, 0xFB, 0x90, 0x64], 38);
+        let expected = 38.0 * 1152.0 / 44100.0;
+
+        let frames = parse_audio_frames(&stream).unwrap();
+        let duration = total_duration(&frames);
+        assert!((duration.as_secs_f64() - expected).abs() < 0.001);
+
+        let duration = stream_duration(&stream).unwrap();
+        assert!((duration.as_secs_f64() - expected).abs() < 0.001);
+
+        assert_eq!(total_duration(&[]), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_total_duration_uses_xing_frame_count() {
+        let mut stream = build_stream([0xFF, 0xFB, 0x90, 0x64], 3);
+
+        // Xing header after the 32 bytes of side info, claiming 1000 frames
+        stream[36..40].copy_from_slice(b"Xing");
+        stream[40..44].copy_from_slice(&0x1_u32.to_be_bytes());
+        stream[44..48].copy_from_slice(&1000_u32.to_be_bytes());
+        let expected = 1000.0 * 1152.0 / 44100.0;
+
+        let frames = parse_audio_frames(&stream).unwrap();
+        let duration = total_duration(&frames);
+        assert!((duration.as_secs_f64() - expected).abs() < 0.001);
+
+        let duration = stream_duration(&stream).unwrap();
+        assert!((duration.as_secs_f64() - expected).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_total_duration_mpeg2_layer3() {
+        // MPEG-2 Layer III frames hold 576 samples, here at 22.05kHz
+        let stream = build_stream([0xFF, 0xF3, 0x90, 0x64], 10);
+        let duration = stream_duration(&stream).unwrap();
+        assert!((duration.as_secs_f64() - 10.0 * 576.0 / 22050.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_extract_audio_range() {
+        // Each frame is 1152 / 44100 ~= 26.1ms long, so seconds 1-2 are covered by frames 38..=76
+        let stream = build_stream([0xFF, 0xFB, 0x90, 0x64], 100);
+
+        let range = extract_audio_range(&stream, Duration::from_secs(1), Duration::from_secs(2));
+        let range = range.unwrap();
+
+        assert_eq!(range.len(), 39 * 417);
+        assert_eq!(range, &stream[38 * 417..77 * 417]);
+        assert_eq!(parse_audio_frames(&range).unwrap().len(), 39);
+
+        let range = extract_audio_range(&stream, Duration::from_secs(10), Duration::from_secs(11));
+        assert!(range.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_extract_audio_range_skips_junk() {
+        let header_bytes = [0xFF, 0xFB, 0x90, 0x64];
+        let mut stream = build_stream(header_bytes, 10);
+        stream.extend_from_slice(&[0x12, 0xFF, 0x00, 0x34]);
+        stream.extend_from_slice(&build_stream(header_bytes, 90));
+
+        let range = extract_audio_range(&stream, Duration::from_secs(1), Duration::from_secs(2));
+
+        assert_eq!(range.unwrap(), &stream[38 * 417 + 4..77 * 417 + 4]);
+    }
+
+    #[test]
+    fn test_extract_audio_range_includes_reservoir() {
+        let mut stream = build_stream([0xFF, 0xFB, 0x90, 0x64], 100);
+
+        // Frame 38 reaches 400 bytes back, past the 381 bytes of main data in frame 37
+        let main_data_begin: u16 = 400;
+        stream[38 * 417 + 4] = (main_data_begin >> 1) as u8;
+        stream[38 * 417 + 5] = (main_data_begin << 7) as u8;
+
+        let range = extract_audio_range(&stream, Duration::from_secs(1), Duration::from_secs(2));
+
+        assert_eq!(range.unwrap(), &stream[36 * 417..77 * 417]);
+    }
 }
@@ -0,0 +1,199 @@
+use super::frame::MP3AudioFrame;
+use super::{DEFAULT_MAX_SKIP, find_next_frame, has_sync_word};
+use crate::error::DecodeError;
+
+/// Lazily parses the audio frames in a byte slice
+///
+/// Each step advances by the frame's `frame_length`, skipping any junk between frames. Iteration
+/// stops at the end of the slice, or after yielding an unrecoverable error.
+///
+/// A last frame cut short by the end of the slice is still yielded, with less `data` than its
+/// `frame_length` implies, whereas `scan` reports it as a `TruncatedFrame` and doesn't count it.
+pub struct FrameParser<'a> {
+    bytes: &'a [u8],
+    current_index: usize,
+
+    /// The maximum number of junk bytes skipped while looking for the next frame
+    max_skip: usize,
+
+    /// The `(offset, length)` of each run of junk skipped between two frames
+    junk_regions: Vec<(usize, usize)>,
+
+    is_done: bool,
+}
+impl<'a> FrameParser<'a> {
+    /// Expects that bytes[0] is the begining of the audio, ie after any ID3v2 tag
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            current_index: 0,
+            max_skip: DEFAULT_MAX_SKIP,
+            junk_regions: Vec::new(),
+            is_done: false,
+        }
+    }
+
+    /// Sets the maximum number of junk bytes skipped between two frames
+    ///
+    /// Junk that runs to the end of the slice (eg an ID3v1 tag) is always ignored
+    pub fn with_max_skip(mut self, max_skip: usize) -> Self {
+        self.max_skip = max_skip;
+        self
+    }
+
+    /// Returns the `(offset, length)` of each run of junk skipped between two frames so far
+    ///
+    /// Junk that runs to the end of the slice (eg an ID3v1 tag) isn't between two frames, so it
+    /// isn't included
+    pub fn junk_regions(&self) -> &[(usize, usize)] {
+        &self.junk_regions
+    }
+
+    /// Moves the parser to the first frame at or after `offset`, eg an offset estimated by
+    /// `vbr::seek_to`, and returns where that frame begins
+    ///
+    /// Returns `None` (and ends iteration) if there is no frame after `offset`
+    pub fn seek(&mut self, offset: usize) -> Result<Option<usize>, DecodeError> {
+        self.current_index = offset.min(self.bytes.len());
+        self.is_done = false;
+
+        let frame_start = find_next_frame(self.bytes, self.current_index, self.max_skip)?;
+        self.current_index = frame_start.unwrap_or(self.bytes.len());
+        Ok(frame_start)
+    }
+}
+impl<'a> Iterator for FrameParser<'a> {
+    type Item = Result<MP3AudioFrame<'a>, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done || self.current_index >= self.bytes.len() {
+            return None;
+        }
+
+        if !has_sync_word(self.bytes, self.current_index) {
+            match find_next_frame(self.bytes, self.current_index, self.max_skip) {
+                Ok(Some(next_index)) => {
+                    self.junk_regions
+                        .push((self.current_index, next_index - self.current_index));
+                    self.current_index = next_index;
+                }
+                Ok(None) => {
+                    // Only junk remains, eg an ID3v1 tag
+                    self.is_done = true;
+                    return None;
+                }
+                Err(e) => {
+                    self.is_done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        match MP3AudioFrame::from_bytes(&self.bytes[self.current_index..]) {
+            Ok(mut frame) => {
+                frame.offset = self.current_index;
+                self.current_index += frame.frame_length as usize;
+                Some(Ok(frame))
+            }
+            Err(e) => {
+                self.is_done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::build_stream;
+
+    #[test]
+    fn test_frame_parser_is_lazy() {
+        let header_bytes = [0xFF, 0xFB, 0x90, 0x64];
+        let mut bytes = vec![0x00; 417 * 3];
+        for frame in bytes.chunks_mut(417) {
+            frame[..4].copy_from_slice(&header_bytes);
+        }
+
+        let mut parser = FrameParser::new(&bytes);
+        assert_eq!(parser.next().unwrap().unwrap().frame_length, 417);
+        assert_eq!(parser.current_index, 417);
+
+        assert_eq!(parser.count(), 2);
+    }
+
+    #[test]
+    fn test_frame_parser_stops_after_error() {
+        let mut bytes = vec![0xFF, 0xFB, 0x90, 0x64];
+        bytes.extend_from_slice(&[0x00; 413]);
+        bytes.extend_from_slice(&[0x00; 100]);
+        bytes.extend_from_slice(&[0xFF, 0xFB, 0x90, 0x64]);
+
+        let mut parser = FrameParser::new(&bytes).with_max_skip(10);
+        assert!(parser.next().unwrap().is_ok());
+        assert!(parser.next().unwrap().is_err());
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn test_frame_parser_seek() {
+        let header_bytes = [0xFF, 0xFB, 0x90, 0x64];
+        let mut bytes = vec![0x00; 417 * 3];
+        for frame in bytes.chunks_mut(417) {
+            frame[..4].copy_from_slice(&header_bytes);
+        }
+
+        let mut parser = FrameParser::new(&bytes);
+        assert_eq!(parser.seek(500).unwrap(), Some(834));
+        assert_eq!(parser.count(), 1);
+
+        let mut parser = FrameParser::new(&bytes);
+        assert_eq!(parser.seek(417).unwrap(), Some(417));
+        assert_eq!(parser.seek(900).unwrap(), None);
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn test_frame_parser_sets_offsets() {
+        let header_bytes = [0xFF, 0xFB, 0x90, 0x64];
+        let padded_header_bytes = [0xFF, 0xFB, 0x92, 0x64];
+        let mut bytes = Vec::new();
+        for header in [
+            header_bytes,
+            padded_header_bytes,
+            header_bytes,
+            header_bytes,
+        ] {
+            let frame_length = MP3AudioFrame::from_bytes(&header).unwrap().frame_length;
+            let mut frame = vec![0x00; frame_length as usize];
+            frame[..4].copy_from_slice(&header);
+            bytes.extend_from_slice(&frame);
+        }
+
+        let frames: Vec<_> = FrameParser::new(&bytes)
+            .take(3)
+            .map(Result::unwrap)
+            .collect();
+
+        let mut expected_offset = 0;
+        for frame in &frames {
+            assert_eq!(frame.offset, expected_offset);
+            expected_offset += frame.frame_length as usize;
+        }
+        assert_eq!(frames[1].byte_range(), 417..835);
+        assert_eq!(&bytes[frames[1].byte_range()][..4], padded_header_bytes);
+    }
+
+    #[test]
+    fn test_frame_parser_junk_regions() {
+        let header_bytes = [0xFF, 0xFB, 0x90, 0x64];
+        let mut bytes = build_stream(header_bytes, 1);
+        bytes.extend_from_slice(&[0x00; 50]);
+        bytes.extend_from_slice(&build_stream(header_bytes, 1));
+
+        let mut parser = FrameParser::new(&bytes);
+        assert_eq!(parser.by_ref().count(), 2);
+        assert_eq!(parser.junk_regions(), [(417, 50)]);
+    }
+}
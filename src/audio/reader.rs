@@ -0,0 +1,316 @@
+use std::io::{self, Read};
+
+use super::frame::{MAX_FREE_FORMAT_LENGTH, MP3AudioFrame};
+use super::{DEFAULT_MAX_SKIP, has_sync_word};
+use crate::error::DecodeError;
+
+/// How many bytes to ask the reader for at a time
+const READ_CHUNK_SIZE: usize = 4096;
+
+/// An audio frame copied out of a stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedFrame {
+    /// The whole frame, starting at the sync word
+    pub bytes: Vec<u8>,
+
+    /// Position of the frame's sync word in the stream
+    pub offset: u64,
+}
+impl OwnedFrame {
+    /// Parses the frame, borrowing from `bytes`
+    pub fn frame(&self) -> MP3AudioFrame<'_> {
+        let mut frame = MP3AudioFrame::from_bytes(&self.bytes)
+            .expect("FrameReader only yields frames with a valid header");
+        frame.offset = self.offset as usize;
+        frame
+    }
+}
+
+/// Lazily reads the audio frames from a `Read`, eg a `BufReader<File>` or a `TcpStream`
+///
+/// The streaming counterpart of `FrameParser`: only the current frame (and, for free-format
+/// streams, up to `MAX_FREE_FORMAT_LENGTH` bytes while looking for the next header) is
+/// buffered, so the whole stream is never held in memory. Junk between frames is skipped. Iteration stops at the end of the stream, or after
+/// yielding an error.
+pub struct FrameReader<R: Read> {
+    reader: R,
+
+    /// Bytes read from the stream but not yet yielded as frames
+    buffer: Vec<u8>,
+
+    /// Position of `buffer[0]` in the stream
+    offset: u64,
+
+    /// The maximum number of junk bytes skipped while looking for the next frame
+    max_skip: usize,
+
+    is_eof: bool,
+    is_done: bool,
+}
+
+impl<R: Read> FrameReader<R> {
+    /// Expects that the reader is at the begining of the audio, ie after any ID3v2 tag
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            offset: 0,
+            max_skip: DEFAULT_MAX_SKIP,
+            is_eof: false,
+            is_done: false,
+        }
+    }
+
+    /// Sets the maximum number of junk bytes skipped between two frames
+    pub fn with_max_skip(mut self, max_skip: usize) -> Self {
+        self.max_skip = max_skip;
+        self
+    }
+
+    /// Reads until at least `needed` bytes are buffered, or the stream ends
+    fn fill(&mut self, needed: usize) -> io::Result<()> {
+        let mut chunk = [0; READ_CHUNK_SIZE];
+        while self.buffer.len() < needed && !self.is_eof {
+            match self.reader.read(&mut chunk) {
+                Ok(0) => self.is_eof = true,
+                Ok(read) => self.buffer.extend_from_slice(&chunk[..read]),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn consume(&mut self, length: usize) -> Vec<u8> {
+        self.offset += length as u64;
+        self.buffer.drain(..length).collect()
+    }
+
+    /// Discards bytes until a frame header is at the start of the buffer. Returns `false` if
+    /// the stream ends first
+    fn resync(&mut self) -> io::Result<bool> {
+        let start = self.offset;
+
+        loop {
+            self.fill(4)?;
+            if self.buffer.len() < 4 {
+                return Ok(false);
+            }
+            // Past junk, a header only counts if it's followed by another one, as `0xFF` bytes
+            // can appear anywhere in the junk
+            if has_sync_word(&self.buffer, 0) && (self.offset == start || self.is_frame_start()?) {
+                return Ok(true);
+            }
+
+            let skipped = (self.offset - start) as usize;
+            if skipped > self.max_skip {
+                return Err(DecodeError::NoFrameFound {
+                    offset: start as usize,
+                    max_skip: self.max_skip,
+                }
+                .into());
+            }
+
+            // Skip to the next byte that could begin a sync word
+            let next = self.buffer[1..]
+                .iter()
+                .position(|&b| b == 0xFF)
+                .map_or(self.buffer.len(), |position| position + 1);
+            self.consume(next);
+        }
+    }
+
+    /// Whether the header at the start of the buffer begins a complete frame followed by
+    /// another sync word (or the end of the stream), like `is_frame_start` for slices
+    fn is_frame_start(&mut self) -> io::Result<bool> {
+        let Ok(frame_length) = self.fill_frame()? else {
+            return Ok(false);
+        };
+
+        self.fill(frame_length + 4)?;
+        Ok((self.is_eof && self.buffer.len() == frame_length)
+            || has_sync_word(&self.buffer, frame_length))
+    }
+
+    /// Buffers the whole of the frame at the start of the buffer, and returns its length
+    ///
+    /// Errors reading the stream are kept apart from errors parsing the frame, so a false sync
+    /// word can be rejected without swallowing the former
+    fn fill_frame(&mut self) -> io::Result<Result<usize, DecodeError>> {
+        loop {
+            // `resync` only buffers the header, so eg a CRC may not have arrived yet
+            let frame = match MP3AudioFrame::from_bytes(&self.buffer) {
+                Err(DecodeError::TruncatedFrame { needed, .. })
+                    if needed > self.buffer.len() && !self.is_eof =>
+                {
+                    self.fill(needed)?;
+                    continue;
+                }
+                Ok(frame) => frame,
+                Err(e) => return Ok(Err(e)),
+            };
+            let frame_length = frame.frame_length as usize;
+
+            // A free-format frame's length is measured up to the next header, so it is only
+            // known once the next header (or the end of the stream) has been buffered
+            let is_complete = match frame.header.bitrate {
+                Some(_) => frame_length <= self.buffer.len(),
+                None => frame_length < self.buffer.len() || self.is_eof,
+            };
+            if is_complete {
+                return Ok(Ok(frame_length));
+            }
+            if self.is_eof {
+                return Ok(Err(DecodeError::TruncatedFrame {
+                    needed: frame_length,
+                    got: self.buffer.len(),
+                }));
+            }
+
+            // Without a next header yet, a free-format frame could be as long as the largest
+            // legal one, so buffer that much in one go rather than a chunk at a time
+            let needed = match frame.header.bitrate {
+                Some(_) => frame_length,
+                None => MAX_FREE_FORMAT_LENGTH + 4,
+            };
+            self.fill(needed)?;
+        }
+    }
+
+    fn next_frame(&mut self) -> io::Result<Option<OwnedFrame>> {
+        if !self.resync()? {
+            // Only junk remains, eg an ID3v1 tag
+            return Ok(None);
+        }
+
+        let frame_length = self.fill_frame()??;
+        let offset = self.offset;
+        Ok(Some(OwnedFrame {
+            bytes: self.consume(frame_length),
+            offset,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for FrameReader<R> {
+    type Item = io::Result<OwnedFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+
+        let frame = self.next_frame().transpose();
+        if !matches!(frame, Some(Ok(_))) {
+            self.is_done = true;
+        }
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::parse_audio_frames;
+    use crate::test_support::{ChunkedReader, build_frame, build_stream};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_frame_reader_matches_slice_parser() {
+        let mut stream = build_stream([0xFF, 0xFB, 0x90, 0x64], 3);
+        stream.extend_from_slice(
+            &[
+                build_frame([0xFF, 0xFB, 0x92, 0x64]),
+                build_frame([0xFF, 0xF3, 0x90, 0x64]),
+            ]
+            .concat(),
+        );
+
+        // Junk between frames, including a header that isn't followed by another one, and an
+        // ID3v1 tag at the end
+        stream.splice(417..417, [0x12, 0xFF, 0xFB, 0x90, 0x64, 0x00]);
+        stream.splice(1257..1257, [0x12, 0xFF, 0x00, 0x34]);
+        stream.extend_from_slice(b"TAG");
+        stream.extend_from_slice(&[0x00; 125]);
+
+        let expected = parse_audio_frames(&stream).unwrap();
+        let frames: Vec<_> = FrameReader::new(Cursor::new(&stream))
+            .collect::<io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(frames.len(), expected.len());
+        assert_eq!(frames[1].offset, 423);
+        for (owned, expected) in frames.iter().zip(&expected) {
+            let frame = owned.frame();
+            assert_eq!(frame.offset, expected.offset);
+            assert_eq!(frame.frame_length, expected.frame_length);
+            assert_eq!(frame.header.sample_rate, expected.header.sample_rate);
+            assert_eq!(frame.main_data(), expected.main_data());
+            assert_eq!(owned.bytes, &stream[expected.byte_range()]);
+        }
+    }
+
+    #[test]
+    fn test_frame_reader_free_format() {
+        let header_bytes = [0xFF, 0xFB, 0x00, 0x64];
+        let mut stream = vec![0x00; 3 * 5000];
+        for frame in stream.chunks_mut(5000) {
+            frame[..4].copy_from_slice(&header_bytes);
+        }
+
+        let frames: Vec<_> = FrameReader::new(Cursor::new(&stream))
+            .collect::<io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(frames.len(), 3);
+        assert!(frames.iter().all(|frame| frame.bytes.len() == 5000));
+    }
+
+    #[test]
+    fn test_frame_reader_small_reads() {
+        // CRC protected, so the frame needs more than the 4 header bytes to parse
+        let stream = build_stream([0xFF, 0xFA, 0x90, 0x64], 3);
+        let reader = ChunkedReader::new(stream.clone(), 1);
+
+        let frames: Vec<_> = FrameReader::new(reader).collect::<io::Result<_>>().unwrap();
+
+        assert_eq!(frames.len(), 3);
+        assert!(frames.iter().all(|frame| frame.frame().header.has_crc()));
+        assert_eq!(
+            frames
+                .iter()
+                .flat_map(|frame| frame.bytes.clone())
+                .collect::<Vec<_>>(),
+            stream
+        );
+    }
+
+    #[test]
+    fn test_frame_reader_free_format_junk() {
+        // A free-format header with no next header within the largest legal frame
+        let mut stream = vec![0xFF, 0xFB, 0x00, 0x64];
+        stream.resize(4 * MAX_FREE_FORMAT_LENGTH, 0x00);
+        let reader = ChunkedReader::new(stream, 1);
+
+        let mut frame_reader = FrameReader::new(reader);
+        let error = frame_reader.next().unwrap().unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert!(frame_reader.buffer.len() <= MAX_FREE_FORMAT_LENGTH + 4);
+    }
+
+    #[test]
+    fn test_frame_reader_truncated_frame() {
+        let mut stream = build_stream([0xFF, 0xFB, 0x90, 0x64], 2);
+        stream.truncate(417 + 100);
+
+        let mut reader = FrameReader::new(Cursor::new(&stream));
+        assert!(reader.next().unwrap().is_ok());
+        assert_eq!(
+            reader.next().unwrap().unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+        assert!(reader.next().is_none());
+    }
+}
@@ -0,0 +1,209 @@
+use super::frame::MP3AudioFrame;
+use super::header::MP3AudioFrameHeader;
+use super::{DEFAULT_MAX_SKIP, find_next_frame};
+use crate::error::DecodeError;
+
+/// Counters gathered by `scan`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanResult {
+    pub frame_count: usize,
+
+    /// The number of samples (per channel) the stream decodes to
+    pub sample_count: u64,
+
+    /// The lowest frame bitrate (in bps), or 0 if there were no frames
+    pub min_bitrate: u32,
+
+    /// The highest frame bitrate (in bps)
+    pub max_bitrate: u32,
+
+    /// The mean of the frame bitrates (in bps)
+    pub average_bitrate: u32,
+
+    /// How many times junk had to be skipped to find the next frame
+    pub resync_count: usize,
+
+    /// Whether every frame had the same sample rate as the first
+    pub has_consistent_sample_rate: bool,
+
+    /// The error that stopped the scan early, if any, eg a `TruncatedFrame` for a file cut off
+    /// mid-frame. The partial frame isn't counted
+    pub error: Option<DecodeError>,
+}
+impl ScanResult {
+    /// Whether the frames' bitrates vary, which doesn't rely on a Xing/VBRI header being present
+    pub fn is_vbr(&self) -> bool {
+        self.min_bitrate != self.max_bitrate
+    }
+
+    /// Whether the whole stream was scanned without skipping junk or hitting an error
+    pub fn is_intact(&self) -> bool {
+        self.resync_count == 0 && self.has_consistent_sample_rate && self.error.is_none()
+    }
+}
+
+/// Walks the audio frames like `parse_audio_frames`, but only accumulates counters
+///
+/// No `MP3AudioFrame`s are built for well-formed streams, so this is cheap enough to validate or
+/// count the frames of a large library. `bytes` is expected to begin at the first audio frame.
+///
+/// Unlike `parse_audio_frames`, which yields a last frame cut short by the end of `bytes`, this
+/// stops with a `TruncatedFrame` error and leaves that frame out of the counts.
+pub fn scan(bytes: &[u8]) -> ScanResult {
+    let mut result = ScanResult {
+        frame_count: 0,
+        sample_count: 0,
+        min_bitrate: 0,
+        max_bitrate: 0,
+        average_bitrate: 0,
+        resync_count: 0,
+        has_consistent_sample_rate: true,
+        error: None,
+    };
+    let mut sample_rate = None;
+    let mut bitrate_sum: u64 = 0;
+    let mut current_index = 0;
+
+    while current_index < bytes.len() {
+        let header = match read_header(bytes, current_index) {
+            Some(header) => header,
+            None => match find_next_frame(bytes, current_index, DEFAULT_MAX_SKIP) {
+                Ok(Some(next_index)) => {
+                    result.resync_count += 1;
+                    current_index = next_index;
+                    continue;
+                }
+                // Only junk remains, eg an ID3v1 tag
+                Ok(None) => break,
+                Err(e) => {
+                    result.error = Some(e);
+                    break;
+                }
+            },
+        };
+
+        let rest = &bytes[current_index..];
+        let data_start = 4 + header.crc_length();
+        if rest.len() < data_start {
+            result.error = Some(DecodeError::TruncatedFrame {
+                needed: data_start,
+                got: rest.len(),
+            });
+            break;
+        }
+
+        let frame_length = match MP3AudioFrame::compute_length(&header, rest) {
+            Ok(frame_length) => frame_length,
+            Err(e) => {
+                result.error = Some(e);
+                break;
+            }
+        };
+        if frame_length as usize > rest.len() {
+            result.error = Some(DecodeError::TruncatedFrame {
+                needed: frame_length as usize,
+                got: rest.len(),
+            });
+            break;
+        }
+        let bitrate = MP3AudioFrame::compute_bitrate(&header, frame_length);
+
+        if result.frame_count == 0 {
+            result.min_bitrate = bitrate;
+        }
+        result.min_bitrate = result.min_bitrate.min(bitrate);
+        result.max_bitrate = result.max_bitrate.max(bitrate);
+        bitrate_sum += bitrate as u64;
+
+        if *sample_rate.get_or_insert(header.sample_rate) != header.sample_rate {
+            result.has_consistent_sample_rate = false;
+        }
+
+        result.frame_count += 1;
+        result.sample_count += header.samples_per_frame() as u64;
+        current_index += frame_length.max(1) as usize;
+    }
+
+    if result.frame_count > 0 {
+        result.average_bitrate = (bitrate_sum / result.frame_count as u64) as u32;
+    }
+    result
+}
+
+fn read_header(bytes: &[u8], index: usize) -> Option<MP3AudioFrameHeader> {
+    let header_bytes = bytes.get(index..index + 4)?.try_into().unwrap();
+    MP3AudioFrameHeader::from_bytes(header_bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::parse_audio_frames;
+    use crate::test_support::build_frame;
+
+    #[test]
+    fn test_scan_counters() {
+        // MPEG-1 Layer III, 44.1kHz at 128kbps and 160kbps, with junk between two frames
+        let cbr_128 = build_frame([0xFF, 0xFB, 0x90, 0x64]);
+        let cbr_160 = build_frame([0xFF, 0xFB, 0xA0, 0x64]);
+        let stream = [
+            cbr_128.clone(),
+            vec![0x00; 10],
+            cbr_160,
+            cbr_128.clone(),
+            cbr_128,
+        ]
+        .concat();
+
+        let result = scan(&stream);
+
+        assert_eq!(result.frame_count, 4);
+        assert_eq!(
+            result.frame_count,
+            parse_audio_frames(&stream).unwrap().len()
+        );
+        assert_eq!(result.sample_count, 4 * 1152);
+        assert_eq!(result.min_bitrate, 128_000);
+        assert_eq!(result.max_bitrate, 160_000);
+        assert_eq!(result.average_bitrate, 136_000);
+        assert_eq!(result.resync_count, 1);
+        assert!(result.has_consistent_sample_rate);
+        assert_eq!(result.error, None);
+        assert!(result.is_vbr());
+        assert!(!result.is_intact());
+    }
+
+    #[test]
+    fn test_scan_truncated_frame() {
+        let frame = build_frame([0xFF, 0xFB, 0x90, 0x64]);
+        let mut stream = frame.repeat(3);
+        stream.truncate(2 * frame.len() + 100);
+
+        let result = scan(&stream);
+
+        assert_eq!(result.frame_count, 2);
+        assert_eq!(
+            result.error,
+            Some(DecodeError::TruncatedFrame {
+                needed: frame.len(),
+                got: 100
+            })
+        );
+        assert!(!result.is_intact());
+    }
+
+    #[test]
+    fn test_scan_cbr_stream() {
+        let frame = build_frame([0xFF, 0xFB, 0x90, 0x64]);
+        let mut stream = frame.repeat(3);
+        // A 48kHz frame
+        stream.extend_from_slice(&build_frame([0xFF, 0xFB, 0x94, 0x64]));
+
+        let result = scan(&stream);
+
+        assert_eq!(result.frame_count, 4);
+        assert!(!result.is_vbr());
+        assert!(!result.has_consistent_sample_rate);
+        assert_eq!(result.resync_count, 0);
+    }
+}
@@ -0,0 +1,565 @@
+use std::time::Duration;
+
+use super::header::MP3AudioFrameHeader;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VbrHeaderKind {
+    /// A "Xing" header, written for VBR streams
+    Xing,
+    /// An "Info" header, the same layout as Xing but written by LAME for CBR streams
+    Info,
+    /// A "VBRI" header, written by the Fraunhofer encoder
+    Vbri,
+}
+
+/// A Xing/Info or VBRI header, stored in place of the audio data of the first frame
+#[derive(Debug)]
+pub struct VbrHeader {
+    pub kind: VbrHeaderKind,
+
+    /// The number of audio frames in the stream, excluding this one
+    pub frame_count: Option<u32>,
+
+    /// The size (in bytes) of the audio in the stream
+    pub byte_count: Option<u32>,
+
+    /// A seek table mapping each percent of the duration to a fraction (out of 256) of
+    /// `byte_count`
+    pub toc: Option<[u8; 100]>,
+
+    /// Encoder quality indicator, from 0 (best) to 100 (worst)
+    pub quality: Option<u32>,
+
+    /// The LAME tag that follows a Xing/Info header written by LAME (or GOGO)
+    pub lame: Option<LameTag>,
+
+    /// The fields only found in VBRI headers
+    pub vbri: Option<VbriFields>,
+}
+
+/// The extension LAME writes after the Xing/Info fields, mostly used for gapless playback
+#[derive(Debug, Clone, PartialEq)]
+pub struct LameTag {
+    /// eg "LAME3.100"
+    pub encoder: String,
+
+    /// Samples added to the start of the stream by the encoder, to be trimmed by the decoder
+    pub encoder_delay: u16,
+
+    /// Samples added to the end of the stream to fill the last frame, to be trimmed too
+    pub padding: u16,
+
+    /// The peak amplitude of the decoded audio, where 1.0 is full scale
+    pub peak_amplitude: f32,
+
+    pub radio_replay_gain: Option<ReplayGain>,
+    pub audiophile_replay_gain: Option<ReplayGain>,
+
+    /// The CRC-16 stored at the end of the tag, covering every byte of the frame before it
+    /// (see `verify_crc`)
+    pub tag_crc: u16,
+
+    /// Position of `tag_crc` in the frame, relative to the sync word
+    crc_offset: usize,
+}
+
+/// A replay gain field of the LAME tag
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayGain {
+    /// Who set the gain, eg 1 for the artist, 3 for automatic analysis
+    pub originator: u8,
+
+    /// The adjustment to apply, in dB
+    pub adjustment_db: f32,
+}
+impl ReplayGain {
+    /// Structure:
+    /// bits\[0..3]  => name (0 when the field isn't set, 1 radio, 2 audiophile)
+    /// bits\[3..6]  => originator
+    /// bits\[6]     => sign (1 is negative)
+    /// bits\[7..16] => the adjustment in tenths of a dB
+    fn from_bytes(bytes: [u8; 2]) -> Option<Self> {
+        let field = u16::from_be_bytes(bytes);
+        if field >> 13 == 0 {
+            return None;
+        }
+
+        let adjustment_db = (field & 0x1FF) as f32 / 10.0;
+        Some(Self {
+            originator: ((field >> 10) & 0b111) as u8,
+            adjustment_db: match (field >> 9) & 0b1 {
+                1 => -adjustment_db,
+                _ => adjustment_db,
+            },
+        })
+    }
+}
+
+impl LameTag {
+    /// Parses a LAME tag, starting at its "LAME" or "GOGO" signature, which sits `offset` bytes
+    /// into the frame
+    ///
+    /// Structure (relative to the signature):
+    /// bytes\[0..9]   => encoder version string, eg "LAME3.100"
+    /// bytes\[9]      => tag revision and VBR method
+    /// bytes\[10]     => lowpass frequency / 100
+    /// bytes\[11..15] => peak amplitude, as a big-endian Q23 fixed-point integer (peak * 2^23)
+    /// bytes\[15..17] => radio replay gain
+    /// bytes\[17..19] => audiophile replay gain
+    /// bytes\[19]     => encoding flags and ATH type
+    /// bytes\[20]     => bitrate
+    /// bytes\[21..24] => encoder delay (12 bits) then padding (12 bits)
+    /// bytes\[24..32] => misc, MP3 gain, preset and music length
+    /// bytes\[32..34] => CRC-16 of the audio data
+    /// bytes\[34..36] => CRC-16 of the frame up to this field
+    fn from_bytes(bytes: &[u8], offset: usize) -> Option<Self> {
+        let bytes = bytes.get(..36)?;
+        if !matches!(&bytes[..4], b"LAME" | b"GOGO") {
+            return None;
+        }
+
+        let encoder = bytes[..9]
+            .iter()
+            .take_while(|&&b| b != 0x00)
+            .map(|&b| b as char)
+            .collect::<String>();
+        let delay_and_padding = u32::from_be_bytes([0, bytes[21], bytes[22], bytes[23]]);
+
+        Some(Self {
+            encoder: encoder.trim_end().to_string(),
+            encoder_delay: (delay_and_padding >> 12) as u16,
+            padding: (delay_and_padding & 0xFFF) as u16,
+            peak_amplitude: u32::from_be_bytes(bytes[11..15].try_into().unwrap()) as f32
+                / (1 << 23) as f32,
+            radio_replay_gain: ReplayGain::from_bytes([bytes[15], bytes[16]]),
+            audiophile_replay_gain: ReplayGain::from_bytes([bytes[17], bytes[18]]),
+            tag_crc: u16::from_be_bytes([bytes[34], bytes[35]]),
+            crc_offset: offset + 34,
+        })
+    }
+
+    /// Verifies `tag_crc`, given the bytes of the frame holding this tag (starting at the sync
+    /// word), eg to detect a Xing/Info header that was edited without updating the CRC
+    ///
+    /// Returns `None` if `frame` ends before the CRC
+    pub fn verify_crc(&self, frame: &[u8]) -> Option<bool> {
+        let covered = frame.get(..self.crc_offset)?;
+        Some(lame_crc16(covered) == self.tag_crc)
+    }
+}
+
+/// Computes the CRC-16 used by LAME tags (polynomial 0x8005 reflected, initial value 0). Unlike
+/// the frame CRC, the bits are processed least significant first
+fn lame_crc16(bytes: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in bytes {
+        crc ^= byte as u16;
+        for _ in 0..8 {
+            crc = match crc & 0x1 {
+                0 => crc >> 1,
+                _ => (crc >> 1) ^ 0xA001,
+            };
+        }
+    }
+    crc
+}
+
+/// The fields of a VBRI header that have no Xing equivalent
+#[derive(Debug, PartialEq, Eq)]
+pub struct VbriFields {
+    pub version: u16,
+
+    /// The encoder delay, in samples
+    pub delay: u16,
+
+    /// The seek table entries are multiplied by this to get a size in bytes
+    pub scale_factor: u16,
+
+    /// How many frames each seek table entry covers
+    pub frames_per_entry: u16,
+
+    /// The (unscaled) size in bytes of each consecutive run of `frames_per_entry` frames
+    pub seek_table: Vec<u32>,
+}
+impl VbrHeader {
+    const FRAME_COUNT_FLAG: u32 = 0x1;
+    const BYTE_COUNT_FLAG: u32 = 0x2;
+    const TOC_FLAG: u32 = 0x4;
+    const QUALITY_FLAG: u32 = 0x8;
+
+    /// Returns the encoder delay and padding (in samples) from the LAME tag, for gapless
+    /// playback
+    ///
+    /// `None` without a LAME tag, in which case no samples should be trimmed
+    pub fn delay_and_padding(&self) -> Option<(u16, u16)> {
+        self.lame
+            .as_ref()
+            .map(|lame| (lame.encoder_delay, lame.padding))
+    }
+
+    /// Parses a Xing/Info or VBRI header from a frame's data (the bytes after the header and
+    /// CRC). Returns `None` if the frame doesn't hold one.
+    pub fn from_frame_data(header: &MP3AudioFrameHeader, data: &[u8]) -> Option<Self> {
+        Self::from_xing_data(header, data).or_else(|| Self::from_vbri_data(header, data))
+    }
+
+    /// Parses a Xing/Info header
+    ///
+    /// The header sits right after the side information, so its offset depends on the MPEG
+    /// version and channel mode.
+    ///
+    /// Structure (relative to the "Xing"/"Info" magic):
+    /// bytes\[0..4]     => "Xing" or "Info" in ASCII
+    /// bytes\[4..8]     => flags, indicating which of the following fields are present
+    /// bytes\[8..12]    => frame count (optional)
+    /// bytes\[12..16]   => byte count (optional)
+    /// bytes\[16..116]  => TOC (optional)
+    /// bytes\[116..120] => quality (optional)
+    /// bytes\[120..156] => LAME tag (optional, see `LameTag`)
+    fn from_xing_data(header: &MP3AudioFrameHeader, data: &[u8]) -> Option<Self> {
+        let bytes = data.get(header.side_info_length()..)?;
+
+        let kind = match bytes.get(..4)? {
+            b"Xing" => VbrHeaderKind::Xing,
+            b"Info" => VbrHeaderKind::Info,
+            _ => return None,
+        };
+        let flags = u32::from_be_bytes(bytes.get(4..8)?.try_into().unwrap());
+
+        // Each field is only present if its flag is set. A truncated field is treated as absent
+        let mut fields = &bytes[8..];
+        let mut next_field = |flag: u32, length: usize| {
+            if flags & flag != flag {
+                return None;
+            }
+
+            let (field, rest) = fields.split_at_checked(length)?;
+            fields = rest;
+            Some(field)
+        };
+
+        let to_u32 = |field: &[u8]| u32::from_be_bytes(field.try_into().unwrap());
+        let frame_count = next_field(Self::FRAME_COUNT_FLAG, 4).map(to_u32);
+        let byte_count = next_field(Self::BYTE_COUNT_FLAG, 4).map(to_u32);
+        let toc = next_field(Self::TOC_FLAG, 100).map(|field| field.try_into().unwrap());
+        let quality = next_field(Self::QUALITY_FLAG, 4).map(to_u32);
+
+        Some(Self {
+            kind,
+            frame_count,
+            byte_count,
+            toc,
+            quality,
+            lame: LameTag::from_bytes(fields, 4 + header.crc_length() + data.len() - fields.len()),
+            vbri: None,
+        })
+    }
+
+    /// Parses a VBRI header, which always sits 32 bytes after the frame header (36 bytes from
+    /// the start of the frame)
+    ///
+    /// Structure (relative to the "VBRI" magic, all fields big-endian):
+    /// bytes\[0..4]   => "VBRI" in ASCII
+    /// bytes\[4..6]   => version
+    /// bytes\[6..8]   => encoder delay
+    /// bytes\[8..10]  => quality
+    /// bytes\[10..14] => byte count
+    /// bytes\[14..18] => frame count
+    /// bytes\[18..20] => number of seek table entries
+    /// bytes\[20..22] => seek table scale factor
+    /// bytes\[22..24] => size (in bytes) of each seek table entry, from 1 to 4
+    /// bytes\[24..26] => frames per seek table entry
+    /// bytes\[26..]   => the seek table
+    fn from_vbri_data(header: &MP3AudioFrameHeader, data: &[u8]) -> Option<Self> {
+        let bytes = data.get((32 - header.crc_length())..)?;
+        if bytes.get(..4)? != b"VBRI" {
+            return None;
+        }
+
+        let fields = bytes.get(..26)?;
+        let to_u16 = |start: usize| u16::from_be_bytes([fields[start], fields[start + 1]]);
+        let to_u32 =
+            |start: usize| u32::from_be_bytes(fields[start..start + 4].try_into().unwrap());
+
+        let entry_count = to_u16(18) as usize;
+        let entry_size = to_u16(22) as usize;
+        if !(1..=4).contains(&entry_size) {
+            return None;
+        }
+
+        let seek_table = bytes
+            .get(26..26 + entry_count * entry_size)?
+            .chunks_exact(entry_size)
+            .map(|entry| entry.iter().fold(0, |value, &b| (value << 8) | b as u32))
+            .collect();
+
+        Some(Self {
+            kind: VbrHeaderKind::Vbri,
+            frame_count: Some(to_u32(14)),
+            byte_count: Some(to_u32(10)),
+            toc: None,
+            quality: Some(to_u16(8) as u32),
+            lame: None,
+            vbri: Some(VbriFields {
+                version: to_u16(4),
+                delay: to_u16(6),
+                scale_factor: to_u16(20),
+                frames_per_entry: to_u16(24),
+                seek_table,
+            }),
+        })
+    }
+}
+
+/// Estimates the byte offset (relative to the first frame) of the audio at `target`, using a
+/// Xing TOC
+///
+/// Each TOC entry gives the position of one percent of `total` as a fraction (out of 256) of
+/// `byte_count`. The offset is interpolated between the entries either side of `target`, so it
+/// is only approximate: use `FrameParser::seek` to move to the frame boundary after it.
+pub fn seek_to(toc: &[u8; 100], byte_count: u32, target: Duration, total: Duration) -> u64 {
+    if total.is_zero() {
+        return 0;
+    }
+
+    let percent = (target.as_secs_f64() / total.as_secs_f64() * 100.0).clamp(0.0, 100.0);
+    let index = (percent as usize).min(99);
+    let before = toc[index] as f64;
+    let after = match toc.get(index + 1) {
+        Some(&entry) => entry as f64,
+        None => 256.0,
+    };
+
+    let fraction = before + (after - before) * (percent - index as f64);
+    (fraction / 256.0 * byte_count as f64) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::frame::MP3AudioFrame;
+    use super::*;
+
+    /// Builds a frame holding a Xing/Info header at `offset` (relative to the frame start)
+    fn build_vbr_frame(
+        header_bytes: [u8; 4],
+        offset: usize,
+        magic: &[u8; 4],
+        flags: u32,
+    ) -> Vec<u8> {
+        let mut bytes = vec![0x00; 417];
+        bytes[..4].copy_from_slice(&header_bytes);
+        bytes[offset..offset + 4].copy_from_slice(magic);
+        bytes[offset + 4..offset + 8].copy_from_slice(&flags.to_be_bytes());
+
+        let mut fields = Vec::new();
+        if flags & 0x1 != 0 {
+            fields.extend_from_slice(&1000_u32.to_be_bytes());
+        }
+        if flags & 0x2 != 0 {
+            fields.extend_from_slice(&417_000_u32.to_be_bytes());
+        }
+        if flags & 0x4 != 0 {
+            fields.extend((0..100).map(|i| (i * 256 / 100) as u8));
+        }
+        if flags & 0x8 != 0 {
+            fields.extend_from_slice(&57_u32.to_be_bytes());
+        }
+        bytes[offset + 8..offset + 8 + fields.len()].copy_from_slice(&fields);
+
+        bytes
+    }
+
+    #[test]
+    fn test_xing_header_mpeg1_stereo() {
+        // Side info is 32 bytes for MPEG-1 stereo, so the magic sits at offset 36
+        let bytes = build_vbr_frame([0xFF, 0xFB, 0x90, 0x64], 36, b"Xing", 0xF);
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+        let vbr = frame.vbr_header().unwrap();
+
+        assert_eq!(vbr.kind, VbrHeaderKind::Xing);
+        assert_eq!(vbr.frame_count, Some(1000));
+        assert_eq!(vbr.byte_count, Some(417_000));
+        assert_eq!(vbr.toc.unwrap()[50], 128);
+        assert_eq!(vbr.quality, Some(57));
+    }
+
+    #[test]
+    fn test_info_header_mpeg1_mono_partial_flags() {
+        // Side info is 17 bytes for MPEG-1 mono, so the magic sits at offset 21
+        let bytes = build_vbr_frame([0xFF, 0xFB, 0x90, 0xC4], 21, b"Info", 0x1);
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+        let vbr = frame.vbr_header().unwrap();
+
+        assert_eq!(vbr.kind, VbrHeaderKind::Info);
+        assert_eq!(vbr.frame_count, Some(1000));
+        assert_eq!(vbr.byte_count, None);
+        assert!(vbr.toc.is_none());
+        assert_eq!(vbr.quality, None);
+    }
+
+    #[test]
+    fn test_delay_and_padding_without_lame_tag() {
+        let bytes = build_vbr_frame([0xFF, 0xFB, 0x90, 0x64], 36, b"Xing", 0xF);
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+        let vbr = frame.vbr_header().unwrap();
+
+        assert!(vbr.lame.is_none());
+        assert_eq!(vbr.delay_and_padding(), None);
+    }
+
+    #[test]
+    fn test_no_vbr_header() {
+        // The magic is at the MPEG-1 mono offset, but this is a stereo frame
+        let bytes = build_vbr_frame([0xFF, 0xFB, 0x90, 0x64], 21, b"Xing", 0x1);
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+
+        assert!(frame.vbr_header().is_none());
+    }
+
+    #[test]
+    fn test_vbri_header() {
+        let mut bytes = vec![0x00; 417];
+        bytes[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x64]);
+        bytes[36..40].copy_from_slice(b"VBRI");
+        #[rustfmt::skip]
+        bytes[40..62].copy_from_slice(&[
+            0x00, 0x01, // version
+            0x02, 0x40, // delay
+            0x00, 0x4B, // quality
+            0x00, 0x06, 0x5C, 0xE8, // byte count
+            0x00, 0x00, 0x03, 0xE8, // frame count
+            0x00, 0x03, // seek table entries
+            0x00, 0x01, // scale factor
+            0x00, 0x02, // bytes per entry
+            0x00, 0x64, // frames per entry
+        ]);
+        bytes[62..68].copy_from_slice(&[0x01, 0x00, 0x02, 0x00, 0x00, 0xFF]);
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+        let vbr = frame.vbr_header().unwrap();
+
+        assert_eq!(vbr.kind, VbrHeaderKind::Vbri);
+        assert_eq!(vbr.frame_count, Some(1000));
+        assert_eq!(vbr.byte_count, Some(417_000));
+        assert_eq!(vbr.quality, Some(75));
+        assert!(vbr.toc.is_none());
+        assert_eq!(
+            vbr.vbri,
+            Some(VbriFields {
+                version: 1,
+                delay: 576,
+                scale_factor: 1,
+                frames_per_entry: 100,
+                seek_table: vec![256, 512, 255],
+            })
+        );
+    }
+
+    #[test]
+    fn test_seek_to() {
+        let total = Duration::from_secs(100);
+        let mut toc = [0; 100];
+        for (index, entry) in toc.iter_mut().enumerate() {
+            *entry = (index * 256 / 100) as u8;
+        }
+        toc[10] = 20;
+        toc[11] = 40;
+
+        // Halfway between the 10% and 11% entries
+        let offset = seek_to(&toc, 256_000, Duration::from_millis(10_500), total);
+        assert_eq!(offset, 30_000);
+
+        // Exactly on an entry
+        assert_eq!(
+            seek_to(&toc, 256_000, Duration::from_secs(50), total),
+            128_000
+        );
+
+        // Past the last entry (253), interpolating towards the end of the audio (256)
+        let offset = seek_to(&toc, 256_000, Duration::from_millis(99_500), total);
+        assert_eq!(offset, 254_500);
+        assert_eq!(
+            seek_to(&toc, 256_000, Duration::from_secs(200), total),
+            256_000
+        );
+    }
+
+    /// Builds a frame holding an Info header and LAME tag, as LAME 3.100 writes them for a
+    /// 128kbps CBR stream
+    fn build_lame_frame() -> Vec<u8> {
+        #[rustfmt::skip]
+        let info_frame: [u8; 156] = {
+            let mut bytes = [0x00; 156];
+            bytes[..8].copy_from_slice(b"Info\x00\x00\x00\x0F");
+            bytes[8..12].copy_from_slice(&[0x00, 0x00, 0x01, 0x9A]);
+            bytes[12..16].copy_from_slice(&[0x00, 0x02, 0x9E, 0x1C]);
+            bytes[116..120].copy_from_slice(&[0x00, 0x00, 0x00, 0x3C]);
+            bytes[120..156].copy_from_slice(&[
+                b'L', b'A', b'M', b'E', b'3', b'.', b'1', b'0', b'0', // encoder
+                0x00, // revision, VBR method
+                0xA5, // lowpass
+                0x00, 0x80, 0x00, 0x00, // peak amplitude
+                0x2E, 0x7B, // radio replay gain
+                0x00, 0x00, // audiophile replay gain
+                0x00, // flags, ATH type
+                0x80, // bitrate
+                0x24, 0x06, 0x3C, // encoder delay, padding
+                0x00, 0x00, 0x00, 0x00, // misc, MP3 gain, preset
+                0x00, 0x02, 0x9E, 0x1C, // music length
+                0x00, 0x00, 0x00, 0x00, // CRCs
+            ]);
+            bytes
+        };
+        let mut bytes = vec![0x00; 417];
+        bytes[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x64]);
+        bytes[36..36 + info_frame.len()].copy_from_slice(&info_frame);
+
+        bytes
+    }
+
+    #[test]
+    fn test_lame_tag() {
+        let bytes = build_lame_frame();
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+        let vbr = frame.vbr_header().unwrap();
+        assert_eq!(vbr.delay_and_padding(), Some((576, 1596)));
+
+        let lame = vbr.lame.unwrap();
+
+        assert_eq!(lame.encoder, "LAME3.100");
+        assert_eq!(lame.encoder_delay, 576);
+        assert_eq!(lame.padding, 1596);
+        // Full scale, ie 0x00800000 / 2^23
+        assert_eq!(lame.peak_amplitude, 1.0);
+        assert_eq!(
+            lame.radio_replay_gain,
+            Some(ReplayGain {
+                originator: 3,
+                adjustment_db: -12.3,
+            })
+        );
+        assert_eq!(lame.audiophile_replay_gain, None);
+    }
+
+    #[test]
+    fn test_lame_tag_crc() {
+        // The CRC-16/ARC check value
+        assert_eq!(lame_crc16(b"123456789"), 0xBB3D);
+
+        // The CRC covers the 190 bytes before it
+        let mut bytes = build_lame_frame();
+        let crc = lame_crc16(&bytes[..190]);
+        bytes[190..192].copy_from_slice(&crc.to_be_bytes());
+
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+        let lame = frame.vbr_header().unwrap().lame.unwrap();
+        assert_eq!(lame.tag_crc, crc);
+        assert_eq!(lame.verify_crc(&bytes), Some(true));
+        assert_eq!(lame.verify_crc(&bytes[..100]), None);
+
+        // The frame count was edited without updating the CRC
+        bytes[47] ^= 0x01;
+        let frame = MP3AudioFrame::from_bytes(&bytes).unwrap();
+        let lame = frame.vbr_header().unwrap().lame.unwrap();
+        assert_eq!(lame.verify_crc(&bytes), Some(false));
+    }
+}
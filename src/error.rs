@@ -0,0 +1,192 @@
+use std::fmt;
+use std::io;
+
+use crate::audio::header::{Layer, MPEGVersion};
+
+/// The errors produced while parsing tags and audio frames
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The first 11 bits of a frame header weren't all set
+    InvalidSyncWord { found: u16 },
+
+    /// The MPEG version bits were `01`
+    ReservedMpegVersion,
+
+    /// The layer bits were `00`
+    ReservedLayer,
+
+    /// The bitrate index was invalid (`1111`)
+    InvalidBitrateIndex(u8),
+
+    /// The layer isn't defined for the MPEG version, eg Layer I in MPEG-2.5
+    InvalidVersionLayer { version: MPEGVersion, layer: Layer },
+
+    /// The sampling rate index was reserved (`11`)
+    InvalidSampleRateIndex(u8),
+
+    /// The emphasis bits were reserved (`10`)
+    ReservedEmphasis,
+
+    /// A header field was given more bits than it holds
+    InvalidFieldBits { field: &'static str, bits: u8 },
+
+    /// The buffer ended before the end of an audio frame
+    TruncatedFrame { needed: usize, got: usize },
+
+    /// No header ended a free-format frame within the largest legal free-format frame length
+    UnterminatedFreeFormatFrame { max_length: usize },
+
+    /// CRC verification isn't supported for this frame's layer
+    UnsupportedCrcLayer,
+
+    /// No valid frame was found within `max_skip` bytes of `offset`
+    NoFrameFound { offset: usize, max_skip: usize },
+
+    /// A start offset was past the end of the buffer
+    InvalidStartOffset { offset: usize, len: usize },
+
+    /// The buffer doesn't begin with an "ID3" tag
+    MissingId3Header,
+
+    /// The tag's major version isn't 2, 3 or 4
+    UnsupportedId3Version(u8),
+
+    /// The buffer ended before the end of an ID3v2 tag
+    TruncatedTag { needed: usize, got: usize },
+
+    /// The buffer ended before the end of a metadata frame
+    TruncatedMetadataFrame { needed: usize, got: usize },
+
+    /// A metadata frame's data is too large for its size field
+    MetadataFrameTooLarge(usize),
+
+    /// A tag's frames don't fit in the size it was to be padded to
+    TagTooLarge { size: usize, target: usize },
+
+    /// A metadata frame's contents didn't match the layout of its ID
+    MalformedMetadataFrame(&'static str),
+
+    /// A text encoding byte other than 0 to 3
+    InvalidTextEncoding(u8),
+
+    /// Text that isn't valid in its declared encoding
+    InvalidText,
+
+    /// A `TSRC` frame that doesn't hold a 12-character ISRC
+    InvalidIsrc(String),
+
+    /// A `TKEY` frame that doesn't hold a musical key
+    InvalidKey(String),
+
+    /// A `COMR` received-as byte other than 0 to 8
+    InvalidReceivedAs(u8),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidSyncWord { found } => {
+                write!(f, "Expected 0x7FF for sync word. Received 0x{found:X}")
+            }
+            Self::ReservedMpegVersion => write!(f, "Reserved MPEG version"),
+            Self::ReservedLayer => write!(f, "Reserved layer"),
+            Self::InvalidBitrateIndex(index) => {
+                write!(f, "Unsupported bitrate index {index:04b}")
+            }
+            Self::InvalidVersionLayer { version, layer } => {
+                write!(f, "{layer:?} isn't defined for {version:?}")
+            }
+            Self::InvalidSampleRateIndex(index) => {
+                write!(f, "Reserved sampling rate index {index:02b}")
+            }
+            Self::ReservedEmphasis => write!(f, "Reserved emphasis"),
+            Self::InvalidFieldBits { field, bits } => {
+                write!(f, "Invalid bits for {field}. Received {bits:08b}")
+            }
+            Self::TruncatedFrame { needed, got } => write!(
+                f,
+                "Truncated frame. Expected atleast {needed} bytes. Received {got}"
+            ),
+            Self::UnterminatedFreeFormatFrame { max_length } => write!(
+                f,
+                "No free-format frame header found within {max_length} bytes"
+            ),
+            Self::UnsupportedCrcLayer => {
+                write!(f, "CRC verification is only supported for Layer III frames")
+            }
+            Self::NoFrameFound { offset, max_skip } => write!(
+                f,
+                "No valid frame found within {max_skip} bytes of offset {offset}"
+            ),
+            Self::InvalidStartOffset { offset, len } => write!(
+                f,
+                "Start offset {offset} is past the end of the buffer ({len} bytes)"
+            ),
+            Self::MissingId3Header => write!(f, "File doesn't have IDV3 header"),
+            Self::UnsupportedId3Version(version) => {
+                write!(f, "Unsupported tag version ID3v2.{version}")
+            }
+            Self::TruncatedTag { needed, got } => write!(
+                f,
+                "Truncated tag. Expected atleast {needed} bytes. Received {got}"
+            ),
+            Self::TruncatedMetadataFrame { needed, got } => write!(
+                f,
+                "Truncated metadata frame. Expected atleast {needed} bytes. Received {got}"
+            ),
+            Self::MetadataFrameTooLarge(size) => {
+                write!(f, "Metadata frame of {size} bytes is too large to write")
+            }
+            Self::TagTooLarge { size, target } => {
+                write!(f, "Tag of {size} bytes doesn't fit in {target} bytes")
+            }
+            Self::MalformedMetadataFrame(reason) => {
+                write!(f, "Malformed metadata frame: {reason}")
+            }
+            Self::InvalidTextEncoding(encoding) => write!(f, "Unknown text encoding {encoding}"),
+            Self::InvalidText => write!(f, "Text isn't valid in its declared encoding"),
+            Self::InvalidIsrc(code) => {
+                write!(f, "Expected a 12-character ISRC. Received {code:?}")
+            }
+            Self::InvalidKey(key) => write!(f, "Expected a musical key. Received {key:?}"),
+            Self::InvalidReceivedAs(byte) => write!(
+                f,
+                "Expected a received-as value from 0 to 8. Received {byte}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<DecodeError> for io::Error {
+    fn from(error: DecodeError) -> Self {
+        let kind = match error {
+            DecodeError::TruncatedFrame { .. }
+            | DecodeError::TruncatedTag { .. }
+            | DecodeError::TruncatedMetadataFrame { .. } => io::ErrorKind::UnexpectedEof,
+            DecodeError::UnsupportedCrcLayer => io::ErrorKind::Unsupported,
+            DecodeError::InvalidStartOffset { .. } | DecodeError::TagTooLarge { .. } => {
+                io::ErrorKind::InvalidInput
+            }
+            _ => io::ErrorKind::InvalidData,
+        };
+
+        io::Error::new(kind, error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_io_error() {
+        let error: io::Error = DecodeError::TruncatedFrame { needed: 4, got: 2 }.into();
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+
+        let error: io::Error = DecodeError::ReservedLayer.into();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "Reserved layer");
+    }
+}
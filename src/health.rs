@@ -0,0 +1,238 @@
+use crate::audio::frame::MP3AudioFrame;
+use crate::audio::header::HeaderWarning;
+use crate::audio::{DEFAULT_MAX_SKIP, find_next_frame};
+use crate::error::DecodeError;
+use crate::metadata::header::ID3v2Header;
+use crate::metadata::trailing::trailing_tags_start;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Worth knowing, but doesn't affect playback
+    Info,
+
+    /// Some audio may be skipped or mistimed
+    Warning,
+
+    /// The file is damaged
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueKind {
+    /// The ID3v2 tag at the start of the file couldn't be parsed
+    InvalidId3v2Tag(DecodeError),
+
+    /// Bytes that aren't part of any frame had to be skipped to find the next frame
+    Resync { skipped: usize },
+
+    /// No further frame could be found, so the rest of the audio was ignored
+    TrailingData { length: usize },
+
+    /// The frame's CRC doesn't match its contents
+    CrcMismatch,
+
+    /// The frame is CRC protected, but checking Layer I and II CRCs isn't supported
+    UnsupportedCrc,
+
+    /// The last frame is cut short
+    TruncatedFrame { needed: usize, got: usize },
+
+    /// The header has a combination of fields that the spec disallows
+    HeaderWarning(HeaderWarning),
+
+    /// The frame count in the Xing/Info or VBRI header doesn't match the number of frames
+    FrameCountMismatch { expected: u32, found: u32 },
+}
+
+impl IssueKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::UnsupportedCrc => Severity::Info,
+            Self::Resync { .. }
+            | Self::TrailingData { .. }
+            | Self::HeaderWarning(_)
+            | Self::FrameCountMismatch { .. } => Severity::Warning,
+            Self::InvalidId3v2Tag(_) | Self::CrcMismatch | Self::TruncatedFrame { .. } => {
+                Severity::Error
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthIssue {
+    pub kind: IssueKind,
+    pub severity: Severity,
+
+    /// Where in the file the issue was found, if it's tied to a position
+    pub offset: Option<usize>,
+}
+
+/// Everything `health_check` found wrong with a file
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct HealthReport {
+    pub issues: Vec<HealthIssue>,
+}
+impl HealthReport {
+    /// Returns the severity of the worst issue, or `None` if the file is clean
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.issues.iter().map(|issue| issue.severity).max()
+    }
+
+    fn push(&mut self, kind: IssueKind, offset: Option<usize>) {
+        self.issues.push(HealthIssue {
+            severity: kind.severity(),
+            kind,
+            offset,
+        });
+    }
+}
+
+/// Checks a whole file (tags and audio) for damage
+///
+/// Unlike `parse_audio_frames`, this doesn't stop at the first problem. It reports every
+/// resync, CRC failure and truncated frame, along with tag errors and VBR header frame counts
+/// that don't match the audio.
+pub fn health_check(bytes: &[u8]) -> HealthReport {
+    let mut report = HealthReport::default();
+
+    let start = match ID3v2Header::from_bytes(bytes) {
+        Ok(header) => (header.size as usize).min(bytes.len()),
+        Err(DecodeError::MissingId3Header) => 0,
+        Err(error) => {
+            report.push(IssueKind::InvalidId3v2Tag(error), Some(0));
+            0
+        }
+    };
+    let end = trailing_tags_start(bytes).max(start);
+
+    let audio = &bytes[start..end];
+    let mut offset = 0;
+    let mut frame_count = 0;
+    let mut expected_frame_count = None;
+
+    while offset < audio.len() {
+        let remaining = &audio[offset..];
+        let frame = match MP3AudioFrame::from_bytes(remaining) {
+            Ok(frame) if frame.frame_length as usize <= remaining.len() => frame,
+            _ => match find_next_frame(audio, offset + 1, DEFAULT_MAX_SKIP) {
+                Ok(Some(next)) => {
+                    let skipped = next - offset;
+                    report.push(IssueKind::Resync { skipped }, Some(start + offset));
+                    offset = next;
+                    continue;
+                }
+                Ok(None) | Err(_) => {
+                    check_trailing_frame(&mut report, remaining, start + offset);
+                    break;
+                }
+            },
+        };
+
+        match frame.verify_crc(remaining) {
+            Ok(true) => {}
+            Ok(false) => report.push(IssueKind::CrcMismatch, Some(start + offset)),
+            Err(_) => report.push(IssueKind::UnsupportedCrc, Some(start + offset)),
+        }
+        for warning in frame.header.warnings() {
+            report.push(IssueKind::HeaderWarning(warning), Some(start + offset));
+        }
+
+        // The VBR header's count excludes the frame holding it
+        match (frame_count, frame.vbr_header()) {
+            (0, Some(vbr)) => expected_frame_count = vbr.frame_count,
+            (_, _) => frame_count += 1,
+        }
+        offset += frame.frame_length as usize;
+    }
+
+    if let Some(expected) = expected_frame_count
+        && expected != frame_count
+    {
+        report.push(
+            IssueKind::FrameCountMismatch {
+                expected,
+                found: frame_count,
+            },
+            None,
+        );
+    }
+
+    report
+}
+
+/// Reports the bytes after which no complete frame could be found, either as a truncated frame
+/// or as unrecognised trailing data
+fn check_trailing_frame(report: &mut HealthReport, remaining: &[u8], offset: usize) {
+    let issue = match MP3AudioFrame::from_bytes(remaining) {
+        Ok(frame) if frame.frame_length as usize > remaining.len() => IssueKind::TruncatedFrame {
+            needed: frame.frame_length as usize,
+            got: remaining.len(),
+        },
+        _ => IssueKind::TrailingData {
+            length: remaining.len(),
+        },
+    };
+    report.push(issue, Some(offset));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::build_stream;
+
+    #[test]
+    fn test_health_check_clean_file() {
+        let mut bytes = vec![b'I', b'D', b'3', 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        bytes.extend_from_slice(&build_stream([0xFF, 0xFB, 0x90, 0x64], 5));
+
+        let report = health_check(&bytes);
+
+        assert_eq!(report.issues, vec![]);
+        assert_eq!(report.max_severity(), None);
+    }
+
+    #[test]
+    fn test_health_check_corrupted_file() {
+        let mut bytes = build_stream([0xFF, 0xFB, 0x90, 0x64], 5);
+
+        // A Xing header claiming 10 frames, when there are only 3 after it
+        bytes[36..40].copy_from_slice(b"Xing");
+        bytes[40..44].copy_from_slice(&0x1_u32.to_be_bytes());
+        bytes[44..48].copy_from_slice(&10_u32.to_be_bytes());
+
+        // Junk between the 3rd and 4th frames, and the last frame cut short
+        bytes.splice(3 * 417..3 * 417, [0x12; 20]);
+        bytes.truncate(bytes.len() - 100);
+
+        let report = health_check(&bytes);
+
+        assert_eq!(
+            report.issues,
+            vec![
+                HealthIssue {
+                    kind: IssueKind::Resync { skipped: 20 },
+                    severity: Severity::Warning,
+                    offset: Some(3 * 417),
+                },
+                HealthIssue {
+                    kind: IssueKind::TruncatedFrame {
+                        needed: 417,
+                        got: 317,
+                    },
+                    severity: Severity::Error,
+                    offset: Some(4 * 417 + 20),
+                },
+                HealthIssue {
+                    kind: IssueKind::FrameCountMismatch {
+                        expected: 10,
+                        found: 3,
+                    },
+                    severity: Severity::Warning,
+                    offset: None,
+                },
+            ]
+        );
+        assert_eq!(report.max_severity(), Some(Severity::Error));
+    }
+}
@@ -0,0 +1,20 @@
+#![deny(clippy::print_stdout, clippy::print_stderr, clippy::dbg_macro)]
+
+pub mod audio;
+pub mod error;
+pub mod health;
+pub mod metadata;
+pub mod output;
+pub mod utils;
+
+#[cfg(test)]
+pub(crate) mod test_support;
+
+pub use crate::audio::frame::MP3AudioFrame;
+pub use crate::audio::header::{
+    ChannelMode, Emphasis, HeaderWarning, Layer, MP3AudioFrameHeader, MPEGVersion, ModeExtension,
+};
+pub use crate::audio::parse_audio_frames;
+pub use crate::error::DecodeError;
+pub use crate::metadata::header::{ID3v2Header, ID3v2MetadataFrame};
+pub use crate::metadata::id3v1::ID3v1Tag;
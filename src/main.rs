@@ -1,35 +1,45 @@
-mod audio;
-mod metadata;
-mod utils;
-
 use std::fs::File;
 use std::io::{Error, Read};
 
-use crate::audio::parse_audio_frames;
-use crate::metadata::header::ID3v2Header;
-use crate::utils::HexSlice;
+use mp3_decoder::metadata::trailing::trailing_tags_start;
+use mp3_decoder::utils::HexSlice;
+use mp3_decoder::{ID3v1Tag, ID3v2Header, parse_audio_frames};
 
 fn main() -> Result<(), Error> {
+    env_logger::init();
+
     let file_path = "./assets/sample_1.mp3";
 
-    let mut file = File::open(&file_path)?;
+    let mut file = File::open(file_path)?;
     let mut buffer = Vec::<u8>::new();
     file.read_to_end(&mut buffer)?;
 
     println!("Read {} bytes", buffer.len());
 
-    let header = ID3v2Header::from_bytes(&buffer)?;
-    println!("Header info");
-    println!("{:?}", header);
+    let start_of_audio = match ID3v2Header::from_bytes(&buffer) {
+        Ok(header) => {
+            println!("Header info");
+            println!("{:?}", header);
+            header.size
+        }
+        Err(e) => {
+            println!("No ID3v2 header: {e}");
+            0
+        }
+    };
+
+    if let Some(tag) = ID3v1Tag::from_file_tail(&buffer) {
+        println!("ID3v1 tag: {:?}", tag);
+    }
 
-    let start_of_audio = header.size;
-    let audio_frames_bytes = &buffer[(start_of_audio as usize)..];
+    let end_of_audio = trailing_tags_start(&buffer).max(start_of_audio as usize);
+    let audio_frames_bytes = &buffer[(start_of_audio as usize)..end_of_audio];
     println!(
         "Audio frames bytes: {}",
         HexSlice::new(&audio_frames_bytes[0..20])
     );
 
-    let audio_frames = parse_audio_frames(&audio_frames_bytes)?;
+    let audio_frames = parse_audio_frames(audio_frames_bytes)?;
     println!("\nAudio Frames: {}", audio_frames.len());
     for frame in audio_frames.iter().take(3) {
         println!("{frame}");
@@ -0,0 +1,99 @@
+use crate::metadata::header::{ID3v2Header, ID3v2MetadataFrame, ID3v2MetadataFrameID};
+
+/// A difference between two ID3v2 tags
+///
+/// `text` fields hold the decoded text of text frames, and are `None` for other frames
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameDiff {
+    Added {
+        id: ID3v2MetadataFrameID,
+        text: Option<String>,
+    },
+    Removed {
+        id: ID3v2MetadataFrameID,
+        text: Option<String>,
+    },
+    Changed {
+        id: ID3v2MetadataFrameID,
+        old: Option<String>,
+        new: Option<String>,
+    },
+}
+
+/// Compares two tags frame by frame
+///
+/// Frames are matched by ID. When a tag has several frames with the same ID (eg multiple `COMM`
+/// frames), they are matched in the order they appear.
+pub fn diff(a: &ID3v2Header, b: &ID3v2Header) -> Vec<FrameDiff> {
+    let mut unmatched: Vec<&ID3v2MetadataFrame> = b.metadata_frames.iter().collect();
+    let mut diffs = Vec::new();
+
+    for old in &a.metadata_frames {
+        let Some(position) = unmatched.iter().position(|new| new.id == old.id) else {
+            diffs.push(FrameDiff::Removed {
+                id: old.id.clone(),
+                text: old.text(),
+            });
+            continue;
+        };
+
+        let new = unmatched.remove(position);
+        if new.data != old.data {
+            diffs.push(FrameDiff::Changed {
+                id: old.id.clone(),
+                old: old.text(),
+                new: new.text(),
+            });
+        }
+    }
+
+    // Whatever is left in `b` has no counterpart in `a`
+    for new in unmatched {
+        diffs.push(FrameDiff::Added {
+            id: new.id.clone(),
+            text: new.text(),
+        });
+    }
+
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{build_metadata_frame, build_tag};
+
+    #[test]
+    fn test_diff() {
+        let artist = build_metadata_frame(b"TPE1", b"\x00Artist");
+        let picture = build_metadata_frame(b"APIC", b"\x00image/png\x00\x03\x00\x89PNG");
+
+        let old_bytes = build_tag(&[build_metadata_frame(b"TIT2", b"\x00Old"), artist.clone()]);
+        let new_bytes = build_tag(&[build_metadata_frame(b"TIT2", b"\x00New"), artist, picture]);
+        let old = ID3v2Header::from_bytes(&old_bytes).unwrap();
+        let new = ID3v2Header::from_bytes(&new_bytes).unwrap();
+
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                FrameDiff::Changed {
+                    id: ID3v2MetadataFrameID::Title,
+                    old: Some("Old".to_string()),
+                    new: Some("New".to_string()),
+                },
+                FrameDiff::Added {
+                    id: ID3v2MetadataFrameID::Custom(b"APIC".to_vec()),
+                    text: None,
+                },
+            ]
+        );
+        assert_eq!(
+            diff(&new, &old)[1],
+            FrameDiff::Removed {
+                id: ID3v2MetadataFrameID::Custom(b"APIC".to_vec()),
+                text: None,
+            }
+        );
+        assert!(diff(&old, &old).is_empty());
+    }
+}
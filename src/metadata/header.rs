@@ -1,6 +1,9 @@
-use std::io::{Error, ErrorKind};
+use std::borrow::Cow;
 
-#[derive(Debug, PartialEq)]
+use crate::error::DecodeError;
+use crate::metadata::id3v1;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum ID3v2MetadataFrameID {
     Title,
     Artist,
@@ -10,6 +13,12 @@ pub enum ID3v2MetadataFrameID {
     TrackNumber,
     Genre,
     Txxx,
+    Isrc,
+    Compilation, // Non-standard iTunes frame
+    TermsOfUse,
+    Commercial,
+    InitialKey,
+    Mood,            // ID3v2.4 only
     Custom(Vec<u8>), // For non-standard frames
 }
 
@@ -24,6 +33,12 @@ impl ID3v2MetadataFrameID {
             ID3v2MetadataFrameID::TrackNumber => b"TRCK",
             ID3v2MetadataFrameID::Genre => b"TCON",
             ID3v2MetadataFrameID::Txxx => b"TXXX",
+            ID3v2MetadataFrameID::Isrc => b"TSRC",
+            ID3v2MetadataFrameID::Compilation => b"TCMP",
+            ID3v2MetadataFrameID::TermsOfUse => b"USER",
+            ID3v2MetadataFrameID::Commercial => b"COMR",
+            ID3v2MetadataFrameID::InitialKey => b"TKEY",
+            ID3v2MetadataFrameID::Mood => b"TMOO",
             ID3v2MetadataFrameID::Custom(bytes) => bytes.as_slice(),
         }
     }
@@ -38,14 +53,33 @@ impl ID3v2MetadataFrameID {
             b"TRCK" => Some(ID3v2MetadataFrameID::TrackNumber),
             b"TCON" => Some(ID3v2MetadataFrameID::Genre),
             b"TXXX" => Some(ID3v2MetadataFrameID::Txxx),
+            b"TSRC" => Some(ID3v2MetadataFrameID::Isrc),
+            b"TCMP" => Some(ID3v2MetadataFrameID::Compilation),
+            b"USER" => Some(ID3v2MetadataFrameID::TermsOfUse),
+            b"COMR" => Some(ID3v2MetadataFrameID::Commercial),
+            b"TKEY" => Some(ID3v2MetadataFrameID::InitialKey),
+            b"TMOO" => Some(ID3v2MetadataFrameID::Mood),
+
+            // ID3v2.2 identifiers
+            b"TT2" => Some(ID3v2MetadataFrameID::Title),
+            b"TP1" => Some(ID3v2MetadataFrameID::Artist),
+            b"TAL" => Some(ID3v2MetadataFrameID::Album),
+            b"TYE" => Some(ID3v2MetadataFrameID::Year),
+            b"COM" => Some(ID3v2MetadataFrameID::Comment),
+            b"TRK" => Some(ID3v2MetadataFrameID::TrackNumber),
+            b"TCO" => Some(ID3v2MetadataFrameID::Genre),
+            b"TXX" => Some(ID3v2MetadataFrameID::Txxx),
+            b"TRC" => Some(ID3v2MetadataFrameID::Isrc),
+            b"TCP" => Some(ID3v2MetadataFrameID::Compilation),
+            b"TKE" => Some(ID3v2MetadataFrameID::InitialKey),
             _ => Some(ID3v2MetadataFrameID::Custom(bytes.to_vec())),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct ID3v2MetadataFrame<'a> {
-    /// 4-char identifier of this frame
+    /// 4-char (3-char for ID3v2.2) identifier of this frame
     pub id: ID3v2MetadataFrameID,
 
     /// The size of this frame's data
@@ -56,57 +90,462 @@ pub struct ID3v2MetadataFrame<'a> {
 
     pub flags: u16,
 
-    pub data: &'a [u8],
+    /// Borrowed from the tag, or owned if the tag had to be decoded first (see
+    /// `reverse_unsynchronisation`)
+    pub data: Cow<'a, [u8]>,
 }
 impl<'a> ID3v2MetadataFrame<'a> {
+    /// Constructs an ID3v2.3/ID3v2.4 frame with no flags set, eg to write a new tag
+    pub fn new(id: ID3v2MetadataFrameID, data: impl Into<Cow<'a, [u8]>>) -> Self {
+        let data = data.into();
+        let data_size = data.len() as u32;
+
+        Self {
+            id,
+            data_size,
+            size: data_size + 10,
+            flags: 0,
+            data,
+        }
+    }
+
     /// Constructs an ID3v2MetadataFrame from bytes
     ///
     /// Expects that bytes[0] is the begining of this section, not the begining of the file
-    pub fn from_bytes(bytes: &'a [u8], version: u8) -> Result<Self, Error> {
+    pub fn from_bytes(bytes: &'a [u8], version: u8) -> Result<Self, DecodeError> {
+        if version == 2 {
+            return Self::from_v2_2_bytes(bytes);
+        }
+
         if bytes.len() < 10 {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "Atleast 10 bytes are required",
-            ));
+            return Err(DecodeError::TruncatedMetadataFrame {
+                needed: 10,
+                got: bytes.len(),
+            });
         }
 
         let data_size = Self::parse_size(&[bytes[4], bytes[5], bytes[6], bytes[7]], version)?;
         let size = data_size + 10;
+        if bytes.len() < size as usize {
+            return Err(DecodeError::TruncatedMetadataFrame {
+                needed: size as usize,
+                got: bytes.len(),
+            });
+        }
+
         Ok(Self {
             id: ID3v2MetadataFrameID::from_bytes(&bytes[..4]).unwrap(),
             data_size,
             size,
             flags: u16::from_be_bytes([bytes[8], bytes[9]]),
-            data: &bytes[10..(size as usize)],
+            data: Cow::Borrowed(&bytes[10..(size as usize)]),
+        })
+    }
+
+    /// Constructs an ID3v2.2 frame, which has a 6-byte header
+    ///
+    /// Structure:
+    /// bytes\[0..3]    => 3-char identifier
+    /// bytes\[3..6]    => Size of the frame's data, as a (non-syncsafe) 24-bit integer
+    /// bytes\[6..size] => The frame's data
+    fn from_v2_2_bytes(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        if bytes.len() < 6 {
+            return Err(DecodeError::TruncatedMetadataFrame {
+                needed: 6,
+                got: bytes.len(),
+            });
+        }
+
+        let data_size = u32::from_be_bytes([0, bytes[3], bytes[4], bytes[5]]);
+        let size = data_size + 6;
+        if bytes.len() < size as usize {
+            return Err(DecodeError::TruncatedMetadataFrame {
+                needed: size as usize,
+                got: bytes.len(),
+            });
+        }
+
+        Ok(Self {
+            id: ID3v2MetadataFrameID::from_bytes(&bytes[..3]).unwrap(),
+            data_size,
+            size,
+            flags: 0, // ID3v2.2 frames have no flags
+            data: Cow::Borrowed(&bytes[6..(size as usize)]),
         })
     }
 
-    fn parse_size(bytes: &[u8; 4], version: u8) -> Result<u32, Error> {
-        if version != 3 {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "Only works with ID3v2.3",
+    /// Serializes the frame for an ID3v2.3 (`version` 3) or ID3v2.4 (`version` 4) tag
+    ///
+    /// The layout is the one read by `from_bytes`. ID3v2.4 sizes are syncsafe. ID3v2.2 frames
+    /// can't be written, so any other `version` returns `DecodeError::UnsupportedId3Version`
+    pub fn to_bytes(&self, version: u8) -> Result<Vec<u8>, DecodeError> {
+        let data_size = u32::try_from(self.data.len())
+            .map_err(|_| DecodeError::MetadataFrameTooLarge(self.data.len()))?;
+        let size = match version {
+            3 => data_size.to_be_bytes(),
+            4 => encode_syncsafe(data_size),
+            _ => return Err(DecodeError::UnsupportedId3Version(version)),
+        };
+
+        let mut bytes = Vec::with_capacity(10 + self.data.len());
+        bytes.extend_from_slice(self.id.to_bytes());
+        bytes.extend_from_slice(&size);
+        bytes.extend_from_slice(&self.flags.to_be_bytes());
+        bytes.extend_from_slice(&self.data);
+        Ok(bytes)
+    }
+
+    /// Decodes the data of a frame from an unsynchronised ID3v2.4 tag, where each frame is
+    /// unsynchronised on its own
+    pub(crate) fn reverse_unsynchronisation(self) -> Self {
+        Self {
+            data: Cow::Owned(reverse_unsynchronisation(&self.data)),
+            ..self
+        }
+    }
+
+    /// Copies the frame's data so it no longer borrows from the tag
+    pub fn into_owned(self) -> ID3v2MetadataFrame<'static> {
+        ID3v2MetadataFrame {
+            id: self.id,
+            data_size: self.data_size,
+            size: self.size,
+            flags: self.flags,
+            data: Cow::Owned(self.data.into_owned()),
+        }
+    }
+
+    /// Decodes the text of a text frame (any frame whose ID starts with 'T')
+    ///
+    /// Returns `None` for other frames, eg `APIC`, and for text that can't be decoded
+    pub fn text(&self) -> Option<String> {
+        if !self.id.to_bytes().starts_with(b"T") {
+            return None;
+        }
+
+        decode_text(&self.data).ok()
+    }
+
+    /// Decodes the International Standard Recording Code of a `TSRC` frame
+    ///
+    /// Returns `Ok(None)` for any other frame, and an error if the code isn't a valid
+    /// 12-character ISRC (eg `USRC17607839`)
+    pub fn isrc(&self) -> Result<Option<String>, DecodeError> {
+        if self.id != ID3v2MetadataFrameID::Isrc {
+            return Ok(None);
+        }
+
+        let code = decode_text(&self.data)?;
+        let chars: Vec<char> = code.chars().collect();
+        let is_valid = chars.len() == 12
+            && chars[..2].iter().all(|c| c.is_ascii_uppercase())
+            && chars[2..5].iter().all(|c| c.is_ascii_alphanumeric())
+            && chars[5..].iter().all(|c| c.is_ascii_digit());
+
+        if !is_valid {
+            return Err(DecodeError::InvalidIsrc(code));
+        }
+
+        Ok(Some(code))
+    }
+
+    /// Decodes the musical key of a `TKEY` frame, eg "Am" or "C#"
+    ///
+    /// Returns `Ok(None)` for any other frame, and an error if the key isn't a root note (A-G),
+    /// followed by an optional sharp ('#') or flat ('b') and an optional minor ('m'). A key of
+    /// "o" (off key) is also accepted.
+    pub fn initial_key(&self) -> Result<Option<String>, DecodeError> {
+        if self.id != ID3v2MetadataFrameID::InitialKey {
+            return Ok(None);
+        }
+
+        let key = decode_text(&self.data)?;
+        let is_valid = match key.as_bytes() {
+            [b'o'] => true,
+            [root, rest @ ..] if (b'A'..=b'G').contains(root) => {
+                matches!(rest, [] | [b'#' | b'b' | b'm'] | [b'#' | b'b', b'm'])
+            }
+            _ => false,
+        };
+
+        if !is_valid {
+            return Err(DecodeError::InvalidKey(key));
+        }
+
+        Ok(Some(key))
+    }
+
+    /// Decodes the text of a `TCON` frame, resolving ID3v1 genre references like "(17)" or "17"
+    /// to their names. Free-text genres are returned unchanged. Returns `None` for any other frame
+    pub fn genre_name(&self) -> Option<String> {
+        if self.id != ID3v2MetadataFrameID::Genre {
+            return None;
+        }
+
+        let genre = self.text()?;
+        let index = genre
+            .strip_prefix('(')
+            .and_then(|rest| rest.strip_suffix(')'))
+            .unwrap_or(&genre);
+
+        match index.parse::<u8>().ok().and_then(id3v1::genre_name) {
+            Some(name) => Some(name.to_string()),
+            None => Some(genre),
+        }
+    }
+
+    /// Decodes the text of a `TMOO` frame, eg "Energetic". Returns `None` for any other frame
+    pub fn mood(&self) -> Option<String> {
+        match self.id {
+            ID3v2MetadataFrameID::Mood => self.text(),
+            _ => None,
+        }
+    }
+
+    /// Decodes a `USER` frame. Returns `Ok(None)` for any other frame
+    ///
+    /// Structure:
+    /// data\[0]    => text encoding
+    /// data\[1..4] => language
+    /// data\[4..]  => the terms of use
+    pub fn terms_of_use(&self) -> Result<Option<TermsOfUse>, DecodeError> {
+        if self.id != ID3v2MetadataFrameID::TermsOfUse {
+            return Ok(None);
+        }
+
+        if self.data.len() < 4 {
+            return Err(DecodeError::MalformedMetadataFrame(
+                "USER frames require atleast 4 bytes",
             ));
         }
 
-        let s = u32::from_be_bytes(*bytes);
-        Ok(s)
+        Ok(Some(TermsOfUse {
+            language: decode_encoded_text(0, &self.data[1..4])?,
+            text: decode_encoded_text(self.data[0], &self.data[4..])?,
+        }))
     }
+
+    /// Decodes how the item in a `COMR` frame was delivered. Returns `Ok(None)` for any other
+    /// frame
+    ///
+    /// Structure:
+    /// text encoding       => 1 byte
+    /// price               => null-terminated ISO-8859-1 string
+    /// valid until         => 8 bytes (YYYYMMDD)
+    /// contact URL         => null-terminated ISO-8859-1 string
+    /// received as         => 1 byte
+    /// ...                 => seller, description and logo (ignored)
+    pub fn received_as(&self) -> Result<Option<ReceivedAs>, DecodeError> {
+        if self.id != ID3v2MetadataFrameID::Commercial {
+            return Ok(None);
+        }
+
+        let truncated = DecodeError::MalformedMetadataFrame("Truncated COMR frame");
+        let end_of_string = |start: usize| {
+            self.data
+                .get(start..)
+                .and_then(|rest| rest.iter().position(|&b| b == 0x00))
+                .map(|length| start + length + 1)
+                .ok_or(truncated.clone())
+        };
+
+        let end_of_price = end_of_string(1)?;
+        let end_of_url = end_of_string(end_of_price + 8)?;
+        let received_as = self.data.get(end_of_url).ok_or(truncated)?;
+
+        ReceivedAs::from_byte(*received_as).map(Some)
+    }
+
+    /// Parses the frame's data size
+    ///
+    /// ID3v2.3 stores it as a plain big-endian u32, while ID3v2.4 stores it as a 28-bit
+    /// syncsafe integer (the top bit of each byte is always 0)
+    fn parse_size(bytes: &[u8; 4], version: u8) -> Result<u32, DecodeError> {
+        match version {
+            3 => Ok(u32::from_be_bytes(*bytes)),
+            4 => Ok(((bytes[0] as u32 & 0x7F) << 21)
+                | ((bytes[1] as u32 & 0x7F) << 14)
+                | ((bytes[2] as u32 & 0x7F) << 7)
+                | (bytes[3] as u32 & 0x7F)),
+            _ => Err(DecodeError::UnsupportedId3Version(version)),
+        }
+    }
+}
+
+/// The contents of a `USER` frame
+#[derive(Debug, PartialEq)]
+pub struct TermsOfUse {
+    /// 3-char ISO-639-2 language code, eg "eng"
+    pub language: String,
+
+    pub text: String,
+}
+
+/// How the item in a `COMR` frame was delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceivedAs {
+    Other,
+    StandardCdAlbum,
+    CompressedAudioOnCd,
+    FileOverInternet,
+    StreamOverInternet,
+    NoteSheets,
+    NoteSheetsInBook,
+    MusicOnOtherMedia,
+    NonMusicalMerchandise,
+}
+impl ReceivedAs {
+    pub fn from_byte(byte: u8) -> Result<Self, DecodeError> {
+        match byte {
+            0x00 => Ok(Self::Other),
+            0x01 => Ok(Self::StandardCdAlbum),
+            0x02 => Ok(Self::CompressedAudioOnCd),
+            0x03 => Ok(Self::FileOverInternet),
+            0x04 => Ok(Self::StreamOverInternet),
+            0x05 => Ok(Self::NoteSheets),
+            0x06 => Ok(Self::NoteSheetsInBook),
+            0x07 => Ok(Self::MusicOnOtherMedia),
+            0x08 => Ok(Self::NonMusicalMerchandise),
+            _ => Err(DecodeError::InvalidReceivedAs(byte)),
+        }
+    }
+}
+
+/// Decodes the data of a text frame
+///
+/// bytes\[0]    => the text encoding (0=ISO-8859-1, 1=UTF-16 with BOM, 2=UTF-16BE, 3=UTF-8)
+/// bytes\[1..]  => the text, optionally terminated by nulls
+fn decode_text(bytes: &[u8]) -> Result<String, DecodeError> {
+    let Some((&encoding, text)) = bytes.split_first() else {
+        return Err(DecodeError::MalformedMetadataFrame(
+            "Text frames require an encoding byte",
+        ));
+    };
+
+    decode_encoded_text(encoding, text)
+}
+
+/// Decodes `text` using the given ID3v2 text encoding
+fn decode_encoded_text(encoding: u8, text: &[u8]) -> Result<String, DecodeError> {
+    let text = match encoding {
+        0 => text.iter().map(|&b| b as char).collect(),
+        1 => match text {
+            [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, u16::from_le_bytes)?,
+            [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, u16::from_be_bytes)?,
+            _ => decode_utf16(text, u16::from_be_bytes)?,
+        },
+        2 => decode_utf16(text, u16::from_be_bytes)?,
+        3 => String::from_utf8(text.to_vec()).map_err(|_| DecodeError::InvalidText)?,
+        _ => return Err(DecodeError::InvalidTextEncoding(encoding)),
+    };
+
+    Ok(text.trim_end_matches('\0').to_string())
+}
+
+fn decode_utf16(bytes: &[u8], to_u16: fn([u8; 2]) -> u16) -> Result<String, DecodeError> {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| to_u16([pair[0], pair[1]]))
+        .collect();
+
+    String::from_utf16(&units).map_err(|_| DecodeError::InvalidText)
+}
+
+/// Encodes a size as a syncsafe integer, ie 7 bits per byte. The inverse of
+/// `ID3v2Header::parse_size`
+fn encode_syncsafe(size: u32) -> [u8; 4] {
+    [21, 14, 7, 0].map(|shift| ((size >> shift) & 0x7F) as u8)
+}
+
+/// Inserts a `0x00` after every `0xFF` that could be mistaken for the start of a sync word, ie
+/// one followed by `0b111xxxxx`, `0x00`, or the end of the data
+pub fn unsynchronise(bytes: &[u8]) -> Vec<u8> {
+    let mut unsynchronised = Vec::with_capacity(bytes.len());
+    for (index, &byte) in bytes.iter().enumerate() {
+        unsynchronised.push(byte);
+        if byte == 0xFF
+            && bytes
+                .get(index + 1)
+                .is_none_or(|&next| next >= 0xE0 || next == 0x00)
+        {
+            unsynchronised.push(0x00);
+        }
+    }
+    unsynchronised
 }
 
-#[derive(Debug)]
+/// Reverses `unsynchronise`, collapsing every `0xFF 0x00` pair back to `0xFF`
+pub fn reverse_unsynchronisation(bytes: &[u8]) -> Vec<u8> {
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut previous = None;
+    for &byte in bytes {
+        if !(previous == Some(0xFF) && byte == 0x00) {
+            decoded.push(byte);
+        }
+        previous = Some(byte);
+    }
+    decoded
+}
+
+/// The flags byte of an ID3v2 tag header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ID3v2Flags {
+    /// `0x80`: The tag has been unsynchronised
+    pub unsynchronisation: bool,
+
+    /// `0x40`: An extended header follows the tag header
+    pub extended_header: bool,
+
+    /// `0x20`: The tag is in an experimental stage
+    pub experimental: bool,
+
+    /// `0x10`: A footer is appended to the tag (ID3v2.4 only)
+    pub footer_present: bool,
+}
+impl ID3v2Flags {
+    /// Whether a footer follows a tag of the given major version. In ID3v2.2/2.3, `0x10` is
+    /// reserved, so a stray bit there is ignored
+    pub(crate) fn has_footer(&self, version: u8) -> bool {
+        self.footer_present && version == 4
+    }
+
+    pub fn from_byte(flags: u8) -> Self {
+        Self {
+            unsynchronisation: (flags & 0x80) == 0x80,
+            extended_header: (flags & 0x40) == 0x40,
+            experimental: (flags & 0x20) == 0x20,
+            footer_present: (flags & 0x10) == 0x10,
+        }
+    }
+
+    pub fn to_byte(&self) -> u8 {
+        (self.unsynchronisation as u8) << 7
+            | (self.extended_header as u8) << 6
+            | (self.experimental as u8) << 5
+            | (self.footer_present as u8) << 4
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct ID3v2Header<'a> {
     pub version: u8,
-    pub flags: u8,
+
+    /// The revision (minor) version, eg the `0` in ID3v2.3.0
+    pub revision: u8,
+
+    pub flags: ID3v2Flags,
 
     /// Size of the metadata after which the audio frames begin.
     ///
     /// Excludes the size of the tag itself (10 bytes)
     pub metadata_size: u32,
 
-    /// The total size of the header
+    /// The total size of the header, including the footer if present
     pub size: u32,
 
+    /// Size of the extended header, or 0 if there isn't one
+    pub extended_header_size: u32,
+
     pub metadata_frames: Vec<ID3v2MetadataFrame<'a>>,
 }
 
@@ -116,56 +555,261 @@ impl<'a> ID3v2Header<'a> {
     /// Structure:
     /// bytes\[0..3]     => represents the 'IDF' name in ASCII
     /// bytes\[4]        => the revision (minor) version. Always 0 in practice
-    /// bytes\[5]        => flags (0x40 means has extra headers)
+    /// bytes\[5]        => flags (see `ID3v2Flags`)
     /// bytes\[6..10]    => Size of header (minus 10 bytes for the actual header data)
-    /// bytes\[10..size] => ID3v2 Metadata frames
-    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, Error> {
-        if !Self::has_flag(&bytes) {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "File doesn't have IDV3 header",
-            ));
+    /// bytes\[10..size] => The extended header, if flagged, then the ID3v2 Metadata frames
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        if !Self::has_flag(bytes) {
+            return Err(DecodeError::MissingId3Header);
         }
 
-        let metadata_size = Self::parse_size(&bytes);
-        let size = metadata_size + 10;
+        let metadata_size = Self::parse_size(bytes);
+        let end_of_metadata = metadata_size + 10;
         let version = bytes[3];
-        let flags = bytes[5];
-        let has_extended_header = Self::has_extended_header(flags);
-        println!("Has extended header: {has_extended_header}");
-        // TODO: Handle case where we have an extended header
+        if !(2..=4).contains(&version) {
+            return Err(DecodeError::UnsupportedId3Version(version));
+        }
+        if bytes.len() < end_of_metadata as usize {
+            return Err(DecodeError::TruncatedTag {
+                needed: end_of_metadata as usize,
+                got: bytes.len(),
+            });
+        }
+
+        let revision = bytes[4];
+        let flags = ID3v2Flags::from_byte(bytes[5]);
+        // ID3v2.4 unsynchronises each frame on its own (so frame sizes count the unsynchronised
+        // bytes), while earlier versions unsynchronise the whole tag
+        let is_tag_unsynchronised = flags.unsynchronisation && version < 4;
+        let metadata = match is_tag_unsynchronised {
+            true => Cow::Owned(reverse_unsynchronisation(
+                &bytes[10..(end_of_metadata as usize)],
+            )),
+            false => Cow::Borrowed(&bytes[10..(end_of_metadata as usize)]),
+        };
+        log::debug!("Has extended header: {}", flags.extended_header);
+        let extended_header_size = match flags.extended_header {
+            true => Self::parse_extended_header_size(&metadata, version)?,
+            false => 0,
+        };
+
+        // Frames parsed from a decoded buffer can't borrow from it past this function
+        let metadata_frames = match metadata {
+            Cow::Borrowed(metadata) => {
+                let frames = Self::build_metadata_frames(
+                    &metadata[extended_header_size as usize..],
+                    version,
+                )?;
+                match flags.unsynchronisation {
+                    true => frames
+                        .into_iter()
+                        .map(ID3v2MetadataFrame::reverse_unsynchronisation)
+                        .collect(),
+                    false => frames,
+                }
+            }
+            Cow::Owned(metadata) => {
+                Self::build_metadata_frames(&metadata[extended_header_size as usize..], version)?
+                    .into_iter()
+                    .map(ID3v2MetadataFrame::into_owned)
+                    .collect()
+            }
+        };
+
+        // The footer is a copy of the header appended after the metadata
+        let size = match flags.has_footer(version) {
+            true => end_of_metadata + 10,
+            false => end_of_metadata,
+        };
 
         Ok(Self {
             version,
+            revision,
             flags,
             metadata_size,
             size,
-            metadata_frames: Self::build_metadata_frames(&bytes[10..(size as usize)], version)?,
+            extended_header_size,
+            metadata_frames,
         })
     }
 
-    fn has_flag(bytes: &[u8]) -> bool {
-        bytes.len() >= 10 && &bytes[0..3] == b"ID3"
+    /// Serializes the tag (header, frames and footer, if flagged)
+    ///
+    /// The extended header and any padding aren't kept when parsing, so they aren't written
+    /// either (see `to_bytes_padded` to add padding). Frames are unsynchronised if the tag's
+    /// unsynchronisation flag is set: the whole tag for ID3v2.3, or each frame's data for
+    /// ID3v2.4.
+    ///
+    /// Only ID3v2.3 and ID3v2.4 tags can be written. ID3v2.2 tags parse fine, but their
+    /// 3-character frame IDs and frame headers have no v2.3 equivalent for every frame, so
+    /// serializing one returns `DecodeError::UnsupportedId3Version(2)`.
+    ///
+    /// Structure:
+    /// bytes\[0..3]     => "ID3" in ASCII
+    /// bytes\[3]        => the major version
+    /// bytes\[4]        => the revision
+    /// bytes\[5]        => flags
+    /// bytes\[6..10]    => syncsafe size of the frames
+    /// bytes\[10..size] => the frames, followed by the footer ("3DI" and a copy of bytes\[3..10])
+    pub fn to_bytes(&self) -> Result<Vec<u8>, DecodeError> {
+        let frames = self.frames_to_bytes()?;
+        let flags = ID3v2Flags {
+            extended_header: false,
+            ..self.flags
+        };
+
+        Ok(self.assemble(flags, &frames, 0))
     }
 
-    fn has_extended_header(flags: u8) -> bool {
-        let mask = 0x40;
-        (flags & mask) == mask
+    /// Serializes the tag like `to_bytes`, then zero-fills after the last frame so the whole tag
+    /// is `target_size` bytes, eg to leave room for later edits without rewriting the file
+    ///
+    /// ID3v2.4 doesn't allow padding alongside a footer, so the footer is left out. Returns
+    /// `DecodeError::TagTooLarge` if the header and frames alone exceed `target_size`.
+    pub fn to_bytes_padded(&self, target_size: usize) -> Result<Vec<u8>, DecodeError> {
+        let frames = self.frames_to_bytes()?;
+        let size = 10 + frames.len();
+        if size > target_size {
+            return Err(DecodeError::TagTooLarge {
+                size,
+                target: target_size,
+            });
+        }
+
+        let flags = ID3v2Flags {
+            extended_header: false,
+            footer_present: false,
+            ..self.flags
+        };
+        let padding = target_size - size;
+        Ok(self.assemble(flags, &frames, padding))
     }
 
-    fn parse_size(bytes: &[u8]) -> u32 {
+    /// Serializes the frames, unsynchronising them if the tag is flagged
+    fn frames_to_bytes(&self) -> Result<Vec<u8>, DecodeError> {
+        let mut frames = Vec::new();
+        for frame in &self.metadata_frames {
+            let frame_bytes = match (self.version, self.flags.unsynchronisation) {
+                (4, true) => ID3v2MetadataFrame {
+                    id: frame.id.clone(),
+                    data: Cow::Owned(unsynchronise(&frame.data)),
+                    ..*frame
+                }
+                .to_bytes(self.version)?,
+                (_, _) => frame.to_bytes(self.version)?,
+            };
+            frames.extend_from_slice(&frame_bytes);
+        }
+        if self.flags.unsynchronisation && self.version < 4 {
+            frames = unsynchronise(&frames);
+        }
+        Ok(frames)
+    }
+
+    /// Writes the header, the serialized `frames`, `padding` zero bytes and the footer (if
+    /// flagged)
+    fn assemble(&self, flags: ID3v2Flags, frames: &[u8], padding: usize) -> Vec<u8> {
+        let mut header = vec![self.version, self.revision, flags.to_byte()];
+        header.extend_from_slice(&encode_syncsafe((frames.len() + padding) as u32));
+
+        let mut bytes = b"ID3".to_vec();
+        bytes.extend_from_slice(&header);
+        bytes.extend_from_slice(frames);
+        bytes.resize(bytes.len() + padding, 0x00);
+        if flags.has_footer(self.version) {
+            bytes.extend_from_slice(b"3DI");
+            bytes.extend_from_slice(&header);
+        }
+        bytes
+    }
+
+    /// Returns the IDs of the metadata frames in this tag, in the order they appear
+    pub fn frame_ids(&self) -> Vec<ID3v2MetadataFrameID> {
+        self.metadata_frames
+            .iter()
+            .map(|frame| frame.id.clone())
+            .collect()
+    }
+
+    /// Whether iTunes' `TCMP` frame marks this track as part of a compilation
+    pub fn is_compilation(&self) -> bool {
+        self.metadata_frames.iter().any(|frame| {
+            frame.id == ID3v2MetadataFrameID::Compilation && frame.text().as_deref() == Some("1")
+        })
+    }
+
+    /// Returns the number of bytes taken up by the metadata frames, excluding any padding
+    pub fn consumed_bytes(&self) -> u32 {
+        self.metadata_frames.iter().map(|frame| frame.size).sum()
+    }
+
+    pub(crate) fn has_flag(bytes: &[u8]) -> bool {
+        bytes.len() >= 10 && &bytes[0..3] == b"ID3"
+    }
+
+    pub(crate) fn parse_size(bytes: &[u8]) -> u32 {
         ((bytes[6] as u32) << 21)
             | ((bytes[7] as u32) << 14)
             | ((bytes[8] as u32) << 7)
             | (bytes[9] as u32)
     }
 
-    fn build_metadata_frames(bytes: &[u8], version: u8) -> Result<Vec<ID3v2MetadataFrame>, Error> {
+    /// Returns the size of the extended header at the start of `metadata`
+    ///
+    /// ID3v2.3: bytes\[0..4] => size, excluding these 4 bytes (6, or 10 with a CRC)
+    ///          bytes\[4..6] => flags, bytes\[6..10] => padding size, bytes\[10..14] => CRC
+    /// ID3v2.4: bytes\[0..4] => syncsafe size, including these 4 bytes
+    ///          bytes\[4]    => number of flag bytes, followed by the flags and their data
+    ///
+    /// ID3v2.2 has no extended header (the flag means compression), so its size is always 0
+    pub(crate) fn parse_extended_header_size(
+        metadata: &[u8],
+        version: u8,
+    ) -> Result<u32, DecodeError> {
+        let size_bytes = match version {
+            3 | 4 => metadata
+                .first_chunk::<4>()
+                .ok_or(DecodeError::TruncatedTag {
+                    needed: 4,
+                    got: metadata.len(),
+                })?,
+            _ => return Ok(0),
+        };
+
+        let size = match version {
+            // A bogus size near u32::MAX would overflow, and couldn't fit in the tag anyway
+            3 => {
+                let size = u32::from_be_bytes(*size_bytes);
+                size.checked_add(4).ok_or(DecodeError::TruncatedTag {
+                    needed: (size as usize).saturating_add(4),
+                    got: metadata.len(),
+                })?
+            }
+            _ => size_bytes.iter().fold(0, |size, &b| (size << 7) | b as u32),
+        };
+        if size as usize > metadata.len() {
+            return Err(DecodeError::TruncatedTag {
+                needed: size as usize,
+                got: metadata.len(),
+            });
+        }
+
+        Ok(size)
+    }
+
+    fn build_metadata_frames(
+        bytes: &[u8],
+        version: u8,
+    ) -> Result<Vec<ID3v2MetadataFrame<'_>>, DecodeError> {
         let mut frames = Vec::new();
-        //println!("Bytes: {} {:?}", bytes.len(), bytes);
 
         let mut current_index = 0;
         while current_index < bytes.len() {
+            // Frame IDs never start with a null byte, so this is the begining of the padding
+            if bytes[current_index] == 0x00 {
+                break;
+            }
+
             let frame = ID3v2MetadataFrame::from_bytes(&bytes[current_index..], version)?;
             current_index += frame.size as usize;
             frames.push(frame);
@@ -174,3 +818,454 @@ impl<'a> ID3v2Header<'a> {
         Ok(frames)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{build_metadata_frame, build_tag};
+
+    #[test]
+    fn test_id3v2_header_revision() {
+        let tag_bytes = [b'I', b'D', b'3', 0x03, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let header = ID3v2Header::from_bytes(&tag_bytes).unwrap();
+
+        assert_eq!(header.version, 3);
+        assert_eq!(header.revision, 1);
+        assert!(header.metadata_frames.is_empty());
+    }
+
+    #[test]
+    fn test_id3v2_header_frame_ids() {
+        let tag_bytes = build_tag(&[
+            build_metadata_frame(b"TIT2", b"\x00Title"),
+            build_metadata_frame(b"TPE1", b"\x00Artist"),
+            build_metadata_frame(b"PRIV", b"owner\x00data"),
+        ]);
+        let header = ID3v2Header::from_bytes(&tag_bytes).unwrap();
+
+        assert_eq!(
+            header.frame_ids(),
+            vec![
+                ID3v2MetadataFrameID::Title,
+                ID3v2MetadataFrameID::Artist,
+                ID3v2MetadataFrameID::Custom(b"PRIV".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_id3v2_flags_from_byte() {
+        let flags = ID3v2Flags::from_byte(0xC0);
+
+        assert!(flags.unsynchronisation);
+        assert!(flags.extended_header);
+        assert!(!flags.experimental);
+        assert!(!flags.footer_present);
+    }
+
+    #[test]
+    fn test_id3v2_header_size_includes_footer() {
+        let tag_bytes = [b'I', b'D', b'3', 0x04, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00];
+        let header = ID3v2Header::from_bytes(&tag_bytes).unwrap();
+
+        assert!(header.flags.footer_present);
+        assert_eq!(header.metadata_size, 0);
+        assert_eq!(header.size, 20);
+    }
+
+    #[test]
+    fn test_id3v2_header_v3_ignores_footer_flag() {
+        let tag_bytes = [b'I', b'D', b'3', 0x03, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00];
+        let header = ID3v2Header::from_bytes(&tag_bytes).unwrap();
+
+        assert_eq!(header.size, 10);
+        assert_eq!(header.to_bytes().unwrap(), tag_bytes);
+    }
+
+    #[test]
+    fn test_id3v2_header_unsupported_version() {
+        let tag_bytes = [b'I', b'D', b'3', 0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let result = ID3v2Header::from_bytes(&tag_bytes);
+
+        assert_eq!(result.unwrap_err(), DecodeError::UnsupportedId3Version(9));
+    }
+
+    #[test]
+    fn test_id3v2_header_consumed_bytes_excludes_padding() {
+        let tag_bytes = build_tag(&[
+            build_metadata_frame(b"TIT2", b"\x00Title"),
+            build_metadata_frame(b"TPE1", b"\x00Artist"),
+            vec![0x00; 64],
+        ]);
+        let header = ID3v2Header::from_bytes(&tag_bytes).unwrap();
+
+        assert_eq!(header.metadata_frames.len(), 2);
+        assert_eq!(header.consumed_bytes(), (10 + 6) + (10 + 7));
+        assert!(header.consumed_bytes() < header.metadata_size);
+    }
+
+    #[test]
+    fn test_id3v2_metadata_frame_isrc() {
+        let frame_bytes = build_metadata_frame(b"TSRC", b"\x00USRC17607839\x00");
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 3).unwrap();
+
+        assert_eq!(frame.id, ID3v2MetadataFrameID::Isrc);
+        assert_eq!(frame.isrc().unwrap(), Some("USRC17607839".to_string()));
+    }
+
+    #[test]
+    fn test_id3v2_metadata_frame_isrc_invalid_length() {
+        let frame_bytes = build_metadata_frame(b"TSRC", b"\x00USRC176078");
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 3).unwrap();
+
+        assert_eq!(
+            frame.isrc().unwrap_err(),
+            DecodeError::InvalidIsrc("USRC176078".to_string())
+        );
+    }
+
+    #[test]
+    fn test_id3v2_header_is_compilation() {
+        let tag_bytes = build_tag(&[
+            build_metadata_frame(b"TIT2", b"\x00Title"),
+            build_metadata_frame(b"TCMP", b"\x001"),
+        ]);
+        let header = ID3v2Header::from_bytes(&tag_bytes).unwrap();
+        assert!(header.is_compilation());
+
+        let tag_bytes = build_tag(&[build_metadata_frame(b"TIT2", b"\x00Title")]);
+        let header = ID3v2Header::from_bytes(&tag_bytes).unwrap();
+        assert!(!header.is_compilation());
+    }
+
+    #[test]
+    fn test_id3v2_metadata_frame_v4_syncsafe_size() {
+        // 0x00 0x00 0x01 0x00 is 128 as a syncsafe integer, but 256 as a plain u32
+        let mut frame_bytes = b"TIT2\x00\x00\x01\x00\x00\x00".to_vec();
+        frame_bytes.push(0x03);
+        frame_bytes.extend_from_slice(&[b'a'; 127]);
+        frame_bytes.extend_from_slice(b"TPE1");
+
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 4).unwrap();
+
+        assert_eq!(frame.id, ID3v2MetadataFrameID::Title);
+        assert_eq!(frame.data_size, 128);
+        assert_eq!(frame.size, 138);
+    }
+
+    #[test]
+    fn test_id3v2_metadata_frame_v3_size() {
+        let mut frame_bytes = b"TIT2\x00\x00\x01\x00\x00\x00".to_vec();
+        frame_bytes.extend_from_slice(&[b'a'; 256]);
+
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 3).unwrap();
+
+        assert_eq!(frame.data_size, 256);
+    }
+
+    #[test]
+    fn test_id3v2_header_v2_2_frames() {
+        let mut body = Vec::new();
+        for (id, text) in [
+            (b"TT2", &b"\x00Title"[..]),
+            (b"TP1", b"\x00Artist"),
+            (b"TAL", b"\x00Album"),
+        ] {
+            body.extend_from_slice(id);
+            body.extend_from_slice(&(text.len() as u32).to_be_bytes()[1..]);
+            body.extend_from_slice(text);
+        }
+
+        let mut tag_bytes = vec![b'I', b'D', b'3', 0x02, 0x00, 0x00, 0x00, 0x00, 0x00];
+        tag_bytes.push(body.len() as u8);
+        tag_bytes.extend_from_slice(&body);
+
+        let header = ID3v2Header::from_bytes(&tag_bytes).unwrap();
+
+        assert_eq!(
+            header.frame_ids(),
+            vec![
+                ID3v2MetadataFrameID::Title,
+                ID3v2MetadataFrameID::Artist,
+                ID3v2MetadataFrameID::Album,
+            ]
+        );
+        assert_eq!(*header.metadata_frames[1].data, *b"\x00Artist");
+    }
+
+    #[test]
+    fn test_id3v2_metadata_frame_terms_of_use() {
+        let frame_bytes = build_metadata_frame(b"USER", b"\x03engAll rights reserved");
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 3).unwrap();
+
+        assert_eq!(
+            frame.terms_of_use().unwrap(),
+            Some(TermsOfUse {
+                language: "eng".to_string(),
+                text: "All rights reserved".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_id3v2_metadata_frame_received_as() {
+        let frame_bytes = build_metadata_frame(
+            b"COMR",
+            b"\x00USD9.99\x0020301231https://example.com\x00\x03Seller\x00Description\x00",
+        );
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 3).unwrap();
+
+        assert_eq!(
+            frame.received_as().unwrap(),
+            Some(ReceivedAs::FileOverInternet)
+        );
+        assert_eq!(frame.terms_of_use().unwrap(), None);
+    }
+
+    #[test]
+    fn test_id3v2_metadata_frame_text_latin1() {
+        let frame_bytes = build_metadata_frame(b"TIT2", b"\x00Caf\xE9\x00");
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 3).unwrap();
+
+        assert_eq!(frame.text(), Some("Café".to_string()));
+    }
+
+    #[test]
+    fn test_id3v2_metadata_frame_text_utf16_bom() {
+        // "Hé" in little-endian UTF-16, with a BOM and a null terminator
+        let frame_bytes = build_metadata_frame(b"TIT2", b"\x01\xFF\xFEH\x00\xE9\x00\x00\x00");
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 3).unwrap();
+        assert_eq!(frame.text(), Some("Hé".to_string()));
+
+        // The same in big-endian UTF-16
+        let frame_bytes = build_metadata_frame(b"TIT2", b"\x01\xFE\xFF\x00H\x00\xE9");
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 3).unwrap();
+        assert_eq!(frame.text(), Some("Hé".to_string()));
+    }
+
+    #[test]
+    fn test_id3v2_metadata_frame_text_non_text_frame() {
+        let frame_bytes = build_metadata_frame(b"APIC", b"\x00image/png\x00\x03\x00\x89PNG");
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 3).unwrap();
+
+        assert_eq!(frame.text(), None);
+    }
+
+    #[test]
+    fn test_id3v2_metadata_frame_initial_key() {
+        let frame_bytes = build_metadata_frame(b"TKEY", b"\x00C#m");
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 3).unwrap();
+        assert_eq!(frame.initial_key().unwrap(), Some("C#m".to_string()));
+
+        let frame_bytes = build_metadata_frame(b"TKEY", b"\x00H#");
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 3).unwrap();
+        assert_eq!(
+            frame.initial_key().unwrap_err(),
+            DecodeError::InvalidKey("H#".to_string())
+        );
+    }
+
+    #[test]
+    fn test_id3v2_metadata_frame_mood() {
+        let frame_bytes = build_metadata_frame(b"TMOO", b"\x03Energetic");
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 4).unwrap();
+
+        assert_eq!(frame.id, ID3v2MetadataFrameID::Mood);
+        assert_eq!(frame.mood(), Some("Energetic".to_string()));
+    }
+
+    #[test]
+    fn test_id3v2_metadata_frame_genre_name() {
+        for (data, expected) in [
+            (&b"\x00(17)"[..], "Rock"),
+            (b"\x0017", "Rock"),
+            (b"\x00Synthpop", "Synthpop"),
+        ] {
+            let frame_bytes = build_metadata_frame(b"TCON", data);
+            let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 3).unwrap();
+            assert_eq!(frame.genre_name(), Some(expected.to_string()));
+        }
+
+        let frame_bytes = build_metadata_frame(b"TIT2", b"\x00(17)");
+        let frame = ID3v2MetadataFrame::from_bytes(&frame_bytes, 3).unwrap();
+        assert_eq!(frame.genre_name(), None);
+    }
+
+    #[test]
+    fn test_id3v2_header_extended_header_v3() {
+        // Size (6, excluding itself), flags, padding size
+        let extended_header = vec![0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        let mut bytes = build_tag(&[extended_header, build_metadata_frame(b"TIT2", b"\x00Title")]);
+        bytes[5] = 0x40;
+
+        let header = ID3v2Header::from_bytes(&bytes).unwrap();
+
+        assert_eq!(header.extended_header_size, 10);
+        assert_eq!(header.frame_ids(), vec![ID3v2MetadataFrameID::Title]);
+    }
+
+    #[test]
+    fn test_id3v2_header_extended_header_v4() {
+        // Syncsafe size (6, including itself), 1 flag byte, no flags set
+        let extended_header = vec![0x00, 0x00, 0x00, 0x06, 0x01, 0x00];
+        let mut frame = build_metadata_frame(b"TIT2", b"\x03Title");
+        frame[4..8].copy_from_slice(&[0x00, 0x00, 0x00, 0x06]);
+        let mut bytes = build_tag(&[extended_header, frame]);
+        bytes[3] = 0x04;
+        bytes[5] = 0x40;
+
+        let header = ID3v2Header::from_bytes(&bytes).unwrap();
+
+        assert_eq!(header.extended_header_size, 6);
+        assert_eq!(header.frame_ids(), vec![ID3v2MetadataFrameID::Title]);
+    }
+
+    #[test]
+    fn test_id3v2_header_extended_header_truncated() {
+        let mut bytes = build_tag(&[vec![0x00, 0x00, 0x00, 0x40, 0x00, 0x00]]);
+        bytes[5] = 0x40;
+
+        assert_eq!(
+            ID3v2Header::from_bytes(&bytes).unwrap_err(),
+            DecodeError::TruncatedTag { needed: 68, got: 6 }
+        );
+    }
+
+    #[test]
+    fn test_id3v2_header_extended_header_size_overflow() {
+        let mut bytes = build_tag(&[vec![0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00]]);
+        bytes[5] = 0x40;
+
+        assert!(matches!(
+            ID3v2Header::from_bytes(&bytes).unwrap_err(),
+            DecodeError::TruncatedTag { got: 6, .. }
+        ));
+    }
+
+    #[test]
+    fn test_unsynchronisation_round_trip() {
+        let bytes = [0xFF, 0xFB, 0x12, 0xFF, 0x00, 0xFF, 0x34, 0xFF];
+        let unsynchronised = unsynchronise(&bytes);
+
+        assert_eq!(
+            unsynchronised,
+            [
+                0xFF, 0x00, 0xFB, 0x12, 0xFF, 0x00, 0x00, 0xFF, 0x34, 0xFF, 0x00
+            ]
+        );
+        assert_eq!(reverse_unsynchronisation(&unsynchronised), bytes);
+    }
+
+    #[test]
+    fn test_id3v2_header_unsynchronisation() {
+        // The text contains a false sync word (0xFF 0xE0), which unsynchronisation breaks up
+        let frame = build_metadata_frame(b"TIT2", b"\x00Title\xFF\xE0");
+        let mut bytes = build_tag(&[unsynchronise(&frame)]);
+        bytes[5] = 0x80;
+
+        let header = ID3v2Header::from_bytes(&bytes).unwrap();
+
+        assert!(header.flags.unsynchronisation);
+        assert_eq!(header.metadata_frames.len(), 1);
+        assert_eq!(*header.metadata_frames[0].data, *b"\x00Title\xFF\xE0");
+        assert_eq!(
+            header.metadata_frames[0].text(),
+            Some("Title\u{FF}\u{E0}".to_string())
+        );
+    }
+
+    #[test]
+    fn test_id3v2_header_to_bytes_round_trip() {
+        let metadata_frames = vec![
+            ID3v2MetadataFrame::new(ID3v2MetadataFrameID::Title, &b"\x00Title"[..]),
+            ID3v2MetadataFrame::new(ID3v2MetadataFrameID::Artist, &b"\x03Artist"[..]),
+        ];
+        let metadata_size = metadata_frames.iter().map(|frame| frame.size).sum();
+        let header = ID3v2Header {
+            version: 3,
+            revision: 0,
+            flags: ID3v2Flags::from_byte(0x00),
+            metadata_size,
+            size: metadata_size + 10,
+            extended_header_size: 0,
+            metadata_frames,
+        };
+
+        let bytes = header.to_bytes().unwrap();
+        assert_eq!(&bytes[..10], b"ID3\x03\x00\x00\x00\x00\x00\x21");
+
+        let parsed = ID3v2Header::from_bytes(&bytes).unwrap();
+        assert_eq!(parsed, header);
+        assert_eq!(parsed.metadata_frames[0].text(), Some("Title".to_string()));
+    }
+
+    #[test]
+    fn test_id3v2_header_to_bytes_v4_unsynchronised() {
+        // ID3v2.4 frame sizes count the unsynchronised data (4 bytes, not 3)
+        let title = build_metadata_frame(b"TIT2", &unsynchronise(b"\x00\xFF\xE0"));
+        let artist = build_metadata_frame(b"TPE1", b"\x00Artist");
+        let mut bytes = build_tag(&[title, artist]);
+        bytes[3] = 0x04;
+        bytes[5] = 0x80;
+
+        let header = ID3v2Header::from_bytes(&bytes).unwrap();
+        assert_eq!(header.metadata_frames[0].data_size, 4);
+        assert_eq!(*header.metadata_frames[0].data, *b"\x00\xFF\xE0");
+        assert_eq!(header.metadata_frames[1].text(), Some("Artist".to_string()));
+
+        let serialized = header.to_bytes().unwrap();
+
+        assert_eq!(serialized, bytes);
+        assert_eq!(ID3v2Header::from_bytes(&serialized).unwrap(), header);
+    }
+
+    #[test]
+    fn test_id3v2_header_to_bytes_padded() {
+        let bytes = build_tag(&[
+            build_metadata_frame(b"TIT2", b"\x00Title"),
+            build_metadata_frame(b"TPE1", b"\x00Artist"),
+        ]);
+        let header = ID3v2Header::from_bytes(&bytes).unwrap();
+
+        let padded = header.to_bytes_padded(4096).unwrap();
+        assert_eq!(padded.len(), 4096);
+        assert_eq!(padded[..6], bytes[..6]);
+        assert_eq!(padded[10..bytes.len()], bytes[10..]);
+        assert!(padded[bytes.len()..].iter().all(|&b| b == 0x00));
+
+        let parsed = ID3v2Header::from_bytes(&padded).unwrap();
+        assert_eq!(parsed.size, 4096);
+        assert_eq!(parsed.metadata_frames, header.metadata_frames);
+        assert_eq!(parsed.consumed_bytes() as usize, bytes.len() - 10);
+
+        assert_eq!(
+            header.to_bytes_padded(bytes.len() - 1),
+            Err(DecodeError::TagTooLarge {
+                size: bytes.len(),
+                target: bytes.len() - 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_id3v2_header_v2_2_to_bytes_unsupported() {
+        let mut body = b"TT2".to_vec();
+        body.extend_from_slice(&[0x00, 0x00, 0x06]);
+        body.extend_from_slice(b"\x00Title");
+
+        let mut tag_bytes = vec![b'I', b'D', b'3', 0x02, 0x00, 0x00, 0x00, 0x00, 0x00];
+        tag_bytes.push(body.len() as u8);
+        tag_bytes.extend_from_slice(&body);
+
+        let header = ID3v2Header::from_bytes(&tag_bytes).unwrap();
+
+        assert_eq!(
+            header.to_bytes(),
+            Err(DecodeError::UnsupportedId3Version(2))
+        );
+        assert_eq!(
+            header.to_bytes_padded(4096),
+            Err(DecodeError::UnsupportedId3Version(2))
+        );
+    }
+}
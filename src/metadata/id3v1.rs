@@ -0,0 +1,211 @@
+/// The standard ID3v1 genre list. ID3v2 `TCON` frames also refer to it by index
+pub const GENRES: [&str; 80] = [
+    "Blues",
+    "Classic Rock",
+    "Country",
+    "Dance",
+    "Disco",
+    "Funk",
+    "Grunge",
+    "Hip-Hop",
+    "Jazz",
+    "Metal",
+    "New Age",
+    "Oldies",
+    "Other",
+    "Pop",
+    "R&B",
+    "Rap",
+    "Reggae",
+    "Rock",
+    "Techno",
+    "Industrial",
+    "Alternative",
+    "Ska",
+    "Death Metal",
+    "Pranks",
+    "Soundtrack",
+    "Euro-Techno",
+    "Ambient",
+    "Trip-Hop",
+    "Vocal",
+    "Jazz+Funk",
+    "Fusion",
+    "Trance",
+    "Classical",
+    "Instrumental",
+    "Acid",
+    "House",
+    "Game",
+    "Sound Clip",
+    "Gospel",
+    "Noise",
+    "AlternRock",
+    "Bass",
+    "Soul",
+    "Punk",
+    "Space",
+    "Meditative",
+    "Instrumental Pop",
+    "Instrumental Rock",
+    "Ethnic",
+    "Gothic",
+    "Darkwave",
+    "Techno-Industrial",
+    "Electronic",
+    "Pop-Folk",
+    "Eurodance",
+    "Dream",
+    "Southern Rock",
+    "Comedy",
+    "Cult",
+    "Gangsta",
+    "Top 40",
+    "Christian Rap",
+    "Pop/Funk",
+    "Jungle",
+    "Native American",
+    "Cabaret",
+    "New Wave",
+    "Psychadelic",
+    "Rave",
+    "Showtunes",
+    "Trailer",
+    "Lo-Fi",
+    "Tribal",
+    "Acid Punk",
+    "Acid Jazz",
+    "Polka",
+    "Retro",
+    "Musical",
+    "Rock & Roll",
+    "Hard Rock",
+];
+
+/// Looks up a genre in the ID3v1 genre list. Returns `None` for unknown indices (eg 255, which
+/// means the genre is unset)
+pub fn genre_name(index: u8) -> Option<&'static str> {
+    GENRES.get(index as usize).copied()
+}
+
+/// An ID3v1 tag, stored in the last 128 bytes of a file
+#[derive(Debug, PartialEq)]
+pub struct ID3v1Tag {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub year: String,
+    pub comment: String,
+
+    /// Only present in ID3v1.1 tags
+    pub track_number: Option<u8>,
+
+    /// Index into the ID3v1 genre list
+    pub genre: u8,
+}
+impl ID3v1Tag {
+    /// Constructs an ID3v1Tag from the end of a file
+    ///
+    /// Expects the whole file (or atleast its last 128 bytes). Returns `None` if the file doesn't
+    /// end with an ID3v1 tag.
+    ///
+    /// Structure (relative to the start of the tag):
+    /// bytes\[0..3]     => "TAG" in ASCII
+    /// bytes\[3..33]    => title
+    /// bytes\[33..63]   => artist
+    /// bytes\[63..93]   => album
+    /// bytes\[93..97]   => year
+    /// bytes\[97..127]  => comment. In ID3v1.1, if bytes\[125] is 0, bytes\[126] is the track
+    /// bytes\[127]      => genre
+    pub fn from_file_tail(bytes: &[u8]) -> Option<Self> {
+        let tag = bytes.last_chunk::<128>()?;
+        if &tag[0..3] != b"TAG" {
+            return None;
+        }
+
+        let (comment, track_number) = match (tag[125], tag[126]) {
+            (0, track) if track != 0 => (&tag[97..125], Some(track)),
+            _ => (&tag[97..127], None),
+        };
+
+        Some(Self {
+            title: Self::parse_string(&tag[3..33]),
+            artist: Self::parse_string(&tag[33..63]),
+            album: Self::parse_string(&tag[63..93]),
+            year: Self::parse_string(&tag[93..97]),
+            comment: Self::parse_string(comment),
+            track_number,
+            genre: tag[127],
+        })
+    }
+
+    /// The name of the tag's genre, if it is in the ID3v1 genre list
+    pub fn genre_name(&self) -> Option<&'static str> {
+        genre_name(self.genre)
+    }
+
+    /// Parses an ISO-8859-1 field, padded with nulls or spaces
+    fn parse_string(bytes: &[u8]) -> String {
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        let text: String = bytes[..end].iter().map(|&b| b as char).collect();
+        text.trim_end().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_tag(comment: &[u8], genre: u8) -> Vec<u8> {
+        let mut tag = vec![0x00; 128];
+        tag[0..3].copy_from_slice(b"TAG");
+        tag[3..8].copy_from_slice(b"Title");
+        tag[33..39].copy_from_slice(b"Artist");
+        tag[63..68].copy_from_slice(b"Album");
+        tag[93..97].copy_from_slice(b"1999");
+        tag[97..97 + comment.len()].copy_from_slice(comment);
+        tag[127] = genre;
+        tag
+    }
+
+    #[test]
+    fn test_id3v1_tag_from_file_tail() {
+        let mut bytes = vec![0xFF, 0xFB, 0x90, 0x64];
+        bytes.extend_from_slice(&build_tag(b"A comment   ", 17));
+
+        let tag = ID3v1Tag::from_file_tail(&bytes).unwrap();
+
+        assert_eq!(tag.title, "Title");
+        assert_eq!(tag.artist, "Artist");
+        assert_eq!(tag.album, "Album");
+        assert_eq!(tag.year, "1999");
+        assert_eq!(tag.comment, "A comment");
+        assert_eq!(tag.track_number, None);
+        assert_eq!(tag.genre, 17);
+        assert_eq!(tag.genre_name(), Some("Rock"));
+    }
+
+    #[test]
+    fn test_id3v1_1_track_number() {
+        let mut bytes = build_tag(b"A comment", 17);
+        bytes[126] = 7;
+
+        let tag = ID3v1Tag::from_file_tail(&bytes).unwrap();
+
+        assert_eq!(tag.comment, "A comment");
+        assert_eq!(tag.track_number, Some(7));
+    }
+
+    #[test]
+    fn test_genre_name() {
+        assert_eq!(genre_name(0), Some("Blues"));
+        assert_eq!(genre_name(79), Some("Hard Rock"));
+        assert_eq!(genre_name(255), None);
+    }
+
+    #[test]
+    fn test_id3v1_tag_missing() {
+        assert!(ID3v1Tag::from_file_tail(&[0x00; 200]).is_none());
+        assert!(ID3v1Tag::from_file_tail(b"TAG").is_none());
+    }
+}
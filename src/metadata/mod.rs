@@ -1 +1,5 @@
+pub mod diff;
 pub mod header;
+pub mod id3v1;
+pub mod reader;
+pub mod trailing;
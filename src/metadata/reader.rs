@@ -0,0 +1,246 @@
+use std::io::{self, Read};
+
+use crate::error::DecodeError;
+use crate::metadata::header::{
+    ID3v2Flags, ID3v2Header, ID3v2MetadataFrame, reverse_unsynchronisation,
+};
+
+/// How many bytes to ask the reader for at a time
+const READ_CHUNK_SIZE: usize = 4096;
+
+/// Reads the metadata frames of an ID3v2 tag from a `Read`, yielding each frame as soon as its
+/// bytes have arrived
+///
+/// Useful when the tag streams in ahead of the audio, eg over the network. Once the iterator
+/// is exhausted, `into_inner` returns the reader positioned at the first byte after the tag.
+///
+/// Unsynchronised ID3v2.2/ID3v2.3 tags have to be decoded as a whole, so they are read up front.
+/// ID3v2.4 unsynchronises each frame on its own, so those frames still stream in.
+pub struct ID3v2FrameReader<R: Read> {
+    reader: R,
+    version: u8,
+    flags: ID3v2Flags,
+
+    /// Bytes read from the tag but not yet yielded as frames
+    buffer: Vec<u8>,
+
+    /// Bytes of the tag not yet read from `reader`
+    remaining: usize,
+
+    is_done: bool,
+}
+
+impl<R: Read> ID3v2FrameReader<R> {
+    /// Reads the tag header (and extended header, if present) from `reader`
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut header = [0; 10];
+        reader.read_exact(&mut header)?;
+        if !ID3v2Header::has_flag(&header) {
+            return Err(DecodeError::MissingId3Header.into());
+        }
+
+        let version = header[3];
+        if !(2..=4).contains(&version) {
+            return Err(DecodeError::UnsupportedId3Version(version).into());
+        }
+
+        let mut frame_reader = Self {
+            reader,
+            version,
+            flags: ID3v2Flags::from_byte(header[5]),
+            buffer: Vec::new(),
+            remaining: ID3v2Header::parse_size(&header) as usize,
+            is_done: false,
+        };
+
+        if frame_reader.flags.unsynchronisation && version < 4 {
+            let mut metadata = vec![0; frame_reader.remaining];
+            frame_reader.reader.read_exact(&mut metadata)?;
+            frame_reader.buffer = reverse_unsynchronisation(&metadata);
+            frame_reader.remaining = 0;
+        }
+
+        if frame_reader.flags.extended_header {
+            let extended_header_size = frame_reader
+                .read_with(|bytes| ID3v2Header::parse_extended_header_size(bytes, version))?;
+            frame_reader.buffer.drain(..extended_header_size as usize);
+        }
+
+        Ok(frame_reader)
+    }
+
+    /// The major version of the tag, eg 3 for ID3v2.3
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Runs `parse` on the buffered bytes, reading more whenever it reports that it needs more
+    fn read_with<T>(&mut self, parse: impl Fn(&[u8]) -> Result<T, DecodeError>) -> io::Result<T> {
+        loop {
+            match parse(&self.buffer) {
+                Err(
+                    DecodeError::TruncatedMetadataFrame { needed, .. }
+                    | DecodeError::TruncatedTag { needed, .. },
+                ) if needed > self.buffer.len() => self.fill(needed)?,
+                result => return result.map_err(io::Error::from),
+            }
+        }
+    }
+
+    /// Reads from the tag until at least `needed` bytes are buffered
+    fn fill(&mut self, needed: usize) -> io::Result<()> {
+        let mut chunk = [0; READ_CHUNK_SIZE];
+        while self.buffer.len() < needed {
+            if self.remaining == 0 {
+                return Err(DecodeError::TruncatedTag {
+                    needed,
+                    got: self.buffer.len(),
+                }
+                .into());
+            }
+
+            let read = self
+                .reader
+                .read(&mut chunk[..self.remaining.min(READ_CHUNK_SIZE)])?;
+            if read == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+
+            self.buffer.extend_from_slice(&chunk[..read]);
+            self.remaining -= read;
+        }
+
+        Ok(())
+    }
+
+    /// Discards the padding and footer, leaving the reader at the end of the tag
+    fn skip_to_end(&mut self) -> io::Result<()> {
+        let footer_size = match self.flags.has_footer(self.version) {
+            true => 10,
+            false => 0,
+        };
+        let to_skip = (self.remaining + footer_size) as u64;
+        io::copy(&mut (&mut self.reader).take(to_skip), &mut io::sink())?;
+
+        self.buffer.clear();
+        self.remaining = 0;
+        Ok(())
+    }
+
+    fn next_frame(&mut self) -> io::Result<Option<ID3v2MetadataFrame<'static>>> {
+        if self.buffer.is_empty() && self.remaining > 0 {
+            self.fill(1)?;
+        }
+
+        // Frame IDs never start with a null byte, so this is the begining of the padding
+        if self.buffer.first().is_none_or(|&b| b == 0x00) {
+            self.skip_to_end()?;
+            return Ok(None);
+        }
+
+        let version = self.version;
+        let mut frame = self.read_with(|bytes| {
+            ID3v2MetadataFrame::from_bytes(bytes, version).map(ID3v2MetadataFrame::into_owned)
+        })?;
+        self.buffer.drain(..frame.size as usize);
+
+        if self.flags.unsynchronisation && version == 4 {
+            frame = frame.reverse_unsynchronisation();
+        }
+
+        Ok(Some(frame))
+    }
+}
+
+impl<R: Read> Iterator for ID3v2FrameReader<R> {
+    type Item = io::Result<ID3v2MetadataFrame<'static>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.is_done {
+            return None;
+        }
+
+        let frame = self.next_frame().transpose();
+        if !matches!(frame, Some(Ok(_))) {
+            self.is_done = true;
+        }
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::header::unsynchronise;
+    use crate::test_support::{ChunkedReader, build_metadata_frame, build_tag};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_id3v2_frame_reader_matches_one_shot_parser() {
+        let mut bytes = build_tag(&[
+            build_metadata_frame(b"TIT2", b"\x00Title"),
+            build_metadata_frame(b"PRIV", &[b'a'; 200]),
+            build_metadata_frame(b"TPE1", b"\x00Artist"),
+            vec![0x00; 16],
+        ]);
+        let header = ID3v2Header::from_bytes(&bytes).unwrap();
+        let expected: Vec<_> = header
+            .metadata_frames
+            .into_iter()
+            .map(|frame| (frame.id, frame.data.into_owned()))
+            .collect();
+        bytes.extend_from_slice(&[0xFF, 0xFB, 0x90, 0x64]);
+
+        let reader = ChunkedReader::new(bytes, 7);
+        let mut frame_reader = ID3v2FrameReader::new(reader).unwrap();
+        let frames: Vec<_> = frame_reader
+            .by_ref()
+            .map(|frame| frame.map(|frame| (frame.id, frame.data.into_owned())))
+            .collect::<io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(frames, expected);
+
+        // The reader is left at the start of the audio
+        let mut audio = Vec::new();
+        frame_reader.into_inner().read_to_end(&mut audio).unwrap();
+        assert_eq!(audio, [0xFF, 0xFB, 0x90, 0x64]);
+    }
+
+    #[test]
+    fn test_id3v2_frame_reader_v4_unsynchronised() {
+        let mut bytes = build_tag(&[
+            build_metadata_frame(b"TIT2", &unsynchronise(b"\x00\xFF\xE0")),
+            build_metadata_frame(b"TPE1", b"\x00Artist"),
+        ]);
+        bytes[3] = 0x04;
+        bytes[5] = 0x80;
+
+        let frames: Vec<_> = ID3v2FrameReader::new(Cursor::new(bytes))
+            .unwrap()
+            .map(|frame| frame.map(|frame| frame.data.into_owned()))
+            .collect::<io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(
+            frames,
+            vec![b"\x00\xFF\xE0".to_vec(), b"\x00Artist".to_vec()]
+        );
+    }
+
+    #[test]
+    fn test_id3v2_frame_reader_truncated_tag() {
+        let mut bytes = build_tag(&[build_metadata_frame(b"TIT2", b"\x00Title")]);
+        bytes.truncate(bytes.len() - 2);
+
+        let mut frame_reader = ID3v2FrameReader::new(Cursor::new(bytes)).unwrap();
+        let error = frame_reader.next().unwrap().unwrap_err();
+
+        assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof);
+        assert!(frame_reader.next().is_none());
+    }
+}
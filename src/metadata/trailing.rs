@@ -0,0 +1,137 @@
+use crate::metadata::id3v1::ID3v1Tag;
+
+/// Size of an APEv2 tag header or footer
+const APE_FOOTER_LENGTH: usize = 32;
+
+/// Returns the offset at which the tags appended after the audio begin, ie the end of the audio
+///
+/// Recognises ID3v1, APEv1/APEv2 and Lyrics3v2 tags, in any order and combination (usually
+/// Lyrics3v2, then APE, then ID3v1). Returns `bytes.len()` if the file has none.
+pub fn trailing_tags_start(bytes: &[u8]) -> usize {
+    let mut end = bytes.len();
+
+    loop {
+        let tail = &bytes[..end];
+        let tag_length = id3v1_length(tail)
+            .or_else(|| ape_length(tail))
+            .or_else(|| lyrics3v2_length(tail));
+
+        match tag_length {
+            Some(length) => end -= length,
+            None => return end,
+        }
+    }
+}
+
+fn id3v1_length(bytes: &[u8]) -> Option<usize> {
+    ID3v1Tag::from_file_tail(bytes).map(|_| 128)
+}
+
+/// Reads the size of an APE tag from its footer
+///
+/// Footer structure (all fields little-endian):
+/// bytes\[0..8]   => "APETAGEX"
+/// bytes\[8..12]  => version, 1000 or 2000
+/// bytes\[12..16] => size of the items and footer, excluding the header
+/// bytes\[16..20] => number of items
+/// bytes\[20..24] => flags. Bit 31 is set if the tag also has a header
+/// bytes\[24..32] => reserved
+fn ape_length(bytes: &[u8]) -> Option<usize> {
+    let footer = bytes.last_chunk::<APE_FOOTER_LENGTH>()?;
+    if &footer[..8] != b"APETAGEX" {
+        return None;
+    }
+
+    let size = u32::from_le_bytes(footer[12..16].try_into().unwrap()) as usize;
+    let flags = u32::from_le_bytes(footer[20..24].try_into().unwrap());
+    let length = match flags & 0x8000_0000 {
+        0 => size,
+        _ => size + APE_FOOTER_LENGTH,
+    };
+
+    (APE_FOOTER_LENGTH..=bytes.len())
+        .contains(&length)
+        .then_some(length)
+}
+
+/// Reads the size of a Lyrics3v2 tag from its end marker
+///
+/// Structure:
+/// "LYRICSBEGIN", the fields, then a 6-digit ASCII size (of everything before it, including
+/// "LYRICSBEGIN") and "LYRICS200"
+fn lyrics3v2_length(bytes: &[u8]) -> Option<usize> {
+    let end_marker = bytes.last_chunk::<15>()?;
+    if &end_marker[6..] != b"LYRICS200" {
+        return None;
+    }
+
+    let size = std::str::from_utf8(&end_marker[..6])
+        .ok()?
+        .parse::<usize>()
+        .ok()?;
+    let length = size + end_marker.len();
+    let start = bytes.len().checked_sub(length)?;
+
+    bytes[start..].starts_with(b"LYRICSBEGIN").then_some(length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::parse_audio_frames;
+    use crate::test_support::build_stream;
+
+    fn build_ape_tag(items: &[u8]) -> Vec<u8> {
+        let mut header = b"APETAGEX".to_vec();
+        header.extend_from_slice(&2000_u32.to_le_bytes());
+        header.extend_from_slice(&((items.len() + 32) as u32).to_le_bytes());
+        header.extend_from_slice(&1_u32.to_le_bytes());
+
+        let mut footer = header.clone();
+        header.extend_from_slice(&0xA000_0000_u32.to_le_bytes());
+        header.extend_from_slice(&[0x00; 8]);
+        footer.extend_from_slice(&0x8000_0000_u32.to_le_bytes());
+        footer.extend_from_slice(&[0x00; 8]);
+
+        [header, items.to_vec(), footer].concat()
+    }
+
+    fn build_id3v1_tag() -> Vec<u8> {
+        let mut tag = vec![0x00; 128];
+        tag[..3].copy_from_slice(b"TAG");
+        tag
+    }
+
+    #[test]
+    fn test_trailing_tags_start_ape_and_id3v1() {
+        let audio = build_stream([0xFF, 0xFB, 0x90, 0x64], 4);
+
+        // The item's value holds a frame header, which mustn't be parsed as audio
+        let mut items = 8_u32.to_le_bytes().to_vec();
+        items.extend_from_slice(&[0x00; 4]);
+        items.extend_from_slice(b"Title\x00\xFF\xFB\x90\x64Text");
+
+        let bytes = [audio.clone(), build_ape_tag(&items), build_id3v1_tag()].concat();
+        let end = trailing_tags_start(&bytes);
+
+        assert_eq!(end, audio.len());
+        assert_eq!(parse_audio_frames(&bytes[..end]).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_trailing_tags_start_lyrics3v2() {
+        let audio = build_stream([0xFF, 0xFB, 0x90, 0x64], 2);
+        let mut lyrics = b"LYRICSBEGININD00003110".to_vec();
+        lyrics.extend_from_slice(format!("{:06}LYRICS200", lyrics.len()).as_bytes());
+
+        let bytes = [audio.clone(), lyrics, build_id3v1_tag()].concat();
+
+        assert_eq!(trailing_tags_start(&bytes), audio.len());
+    }
+
+    #[test]
+    fn test_trailing_tags_start_no_tags() {
+        let audio = build_stream([0xFF, 0xFB, 0x90, 0x64], 2);
+        assert_eq!(trailing_tags_start(&audio), audio.len());
+    }
+}
@@ -0,0 +1,118 @@
+use crate::audio::header::Emphasis;
+
+/// Time constants of the 50/15 µs emphasis curve, in seconds
+const TIME_CONSTANT_POLE: f64 = 50e-6;
+const TIME_CONSTANT_ZERO: f64 = 15e-6;
+
+/// Whether `apply_deemphasis` filters the samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeemphasisMode {
+    /// Reverse the emphasis signalled by the header
+    Apply,
+
+    /// Leave the samples unchanged, eg for callers that apply their own EQ
+    Skip,
+}
+
+/// Reverses the emphasis signalled by a frame header, filtering interleaved samples in place
+///
+/// Returns whether de-emphasis was applied. With `DeemphasisMode::Skip` it never is, and the
+/// caller can still tell from `emphasis` that it was signalled. Only 50/15 µs emphasis is
+/// supported; samples with `Emphasis::CcittJ17` (or no emphasis) are left unchanged.
+pub fn apply_deemphasis(
+    samples: &mut [f32],
+    sample_rate: u32,
+    channels: u16,
+    emphasis: Emphasis,
+    mode: DeemphasisMode,
+) -> bool {
+    if mode == DeemphasisMode::Skip
+        || emphasis != Emphasis::Ms5015
+        || channels == 0
+        || sample_rate == 0
+    {
+        return false;
+    }
+
+    // The bilinear transform of H(s) = (1 + s * 15µs) / (1 + s * 50µs): unity gain at DC,
+    // falling to 15/50 (about -10.5 dB) at high frequencies
+    let k = 2.0 * sample_rate as f64;
+    let a0 = 1.0 + k * TIME_CONSTANT_POLE;
+    let b0 = (1.0 + k * TIME_CONSTANT_ZERO) / a0;
+    let b1 = (1.0 - k * TIME_CONSTANT_ZERO) / a0;
+    let a1 = (1.0 - k * TIME_CONSTANT_POLE) / a0;
+
+    let channels = channels as usize;
+    for channel in 0..channels {
+        let (mut previous_x, mut previous_y) = (0.0, 0.0);
+        for sample in samples.iter_mut().skip(channel).step_by(channels) {
+            let x = *sample as f64;
+            let y = b0 * x + b1 * previous_x - a1 * previous_y;
+            (previous_x, previous_y) = (x, y);
+            *sample = y as f32;
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_deemphasis() {
+        // A constant left channel, and a right channel at the Nyquist frequency
+        let mut samples: Vec<f32> = (0..2000)
+            .flat_map(|index| [0.5, if index % 2 == 0 { 0.5 } else { -0.5 }])
+            .collect();
+
+        assert!(apply_deemphasis(
+            &mut samples,
+            44100,
+            2,
+            Emphasis::Ms5015,
+            DeemphasisMode::Apply
+        ));
+
+        // Once the filter settles, DC passes unchanged and the Nyquist frequency is scaled by
+        // 15/50
+        let last_frame = &samples[samples.len() - 2..];
+        assert!((last_frame[0] - 0.5).abs() < 1e-3);
+        assert!((last_frame[1].abs() - 0.5 * 0.3).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_apply_deemphasis_passthrough() {
+        let original = [0.5, -0.5, 0.25, -0.25];
+
+        for emphasis in [Emphasis::None, Emphasis::CcittJ17] {
+            let mut samples = original;
+            assert!(!apply_deemphasis(
+                &mut samples,
+                44100,
+                2,
+                emphasis,
+                DeemphasisMode::Apply
+            ));
+            assert_eq!(samples, original);
+        }
+    }
+
+    #[test]
+    fn test_apply_deemphasis_skip() {
+        let original = [0.5, -0.5, 0.25, -0.25];
+        let mut samples = original;
+
+        let emphasis_applied = apply_deemphasis(
+            &mut samples,
+            44100,
+            2,
+            Emphasis::Ms5015,
+            DeemphasisMode::Skip,
+        );
+
+        assert!(!emphasis_applied);
+        assert_eq!(samples, original);
+    }
+}
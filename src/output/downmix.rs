@@ -0,0 +1,55 @@
+/// Averages interleaved samples down to a single channel
+///
+/// `channels` would usually come from `header.channel_mode().channel_count()`, so
+/// `ChannelMode::SingleChannel` audio is passed through unchanged. A trailing sample frame
+/// with fewer than `channels` samples is incomplete, and is dropped.
+pub fn downmix_to_mono(samples: &[f32], channels: u16) -> Vec<f32> {
+    match channels {
+        0 => Vec::new(),
+        1 => samples.to_vec(),
+        _ => samples
+            .chunks_exact(channels as usize)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect(),
+    }
+}
+
+/// Extracts a single channel (eg 0 for left, 1 for right) from interleaved samples
+///
+/// Returns an empty `Vec` if `channel` is out of range. As with `downmix_to_mono`, a trailing
+/// incomplete sample frame is dropped.
+pub fn extract_channel(samples: &[f32], channels: u16, channel: u16) -> Vec<f32> {
+    if channel >= channels {
+        return Vec::new();
+    }
+
+    samples
+        .chunks_exact(channels as usize)
+        .map(|frame| frame[channel as usize])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_downmix_to_mono() {
+        let stereo = [1.0, 0.0, 0.5, 0.5, -1.0, 0.5, 0.25];
+
+        // The trailing left sample has no right sample to average with
+        assert_eq!(downmix_to_mono(&stereo, 2), [0.5, 0.5, -0.25]);
+        assert_eq!(downmix_to_mono(&stereo, 1), stereo);
+        assert!(downmix_to_mono(&stereo, 0).is_empty());
+    }
+
+    #[test]
+    fn test_extract_channel() {
+        let stereo = [1.0, 0.0, 0.5, -0.5, -1.0, 0.25, 0.75];
+
+        assert_eq!(extract_channel(&stereo, 2, 0), [1.0, 0.5, -1.0]);
+        assert_eq!(extract_channel(&stereo, 2, 1), [0.0, -0.5, 0.25]);
+        assert_eq!(extract_channel(&stereo, 1, 0), stereo);
+        assert!(extract_channel(&stereo, 2, 2).is_empty());
+    }
+}
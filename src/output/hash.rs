@@ -0,0 +1,58 @@
+use std::hash::Hasher;
+
+use super::pcm::to_i16;
+
+/// Feeds interleaved samples into `hasher`, eg to detect duplicate audio across copies of a file
+/// that only differ in their tags
+///
+/// Samples are quantized to 16-bit PCM first, exactly as `write_pcm_to` writes them (in
+/// little-endian order), so floating point noise below the 16-bit resolution doesn't change the
+/// hash. Pick a hasher whose output is stable if hashes are stored, which `DefaultHasher` isn't.
+pub fn hash_samples<H: Hasher>(samples: &[f32], hasher: &mut H) {
+    for &sample in samples {
+        hasher.write(&to_i16(sample).to_le_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::pcm::{Endianness, write_pcm_to};
+
+    /// Keeps every byte written, so the input to the hash can be compared
+    #[derive(Default)]
+    struct RecordingHasher(Vec<u8>);
+    impl Hasher for RecordingHasher {
+        fn finish(&self) -> u64 {
+            self.0.len() as u64
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+    }
+
+    #[test]
+    fn test_hash_samples_matches_pcm() {
+        let samples = [0.0, 0.5, -0.5, 1.0, -2.0];
+        let mut hasher = RecordingHasher::default();
+        hash_samples(&samples, &mut hasher);
+
+        let mut pcm = Vec::new();
+        write_pcm_to(&mut pcm, &samples, Endianness::Little).unwrap();
+
+        assert_eq!(hasher.0, pcm);
+    }
+
+    #[test]
+    fn test_hash_samples_ignores_sub_lsb_noise() {
+        let hash = |samples: &[f32]| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            hash_samples(samples, &mut hasher);
+            hasher.finish()
+        };
+
+        assert_eq!(hash(&[0.5, -0.25]), hash(&[0.500001, -0.250001]));
+        assert_ne!(hash(&[0.5, -0.25]), hash(&[0.5, 0.25]));
+    }
+}
@@ -0,0 +1,196 @@
+use std::f64::consts::PI;
+
+/// Length of a gating block, in seconds
+const BLOCK_DURATION: f64 = 0.4;
+
+/// Gating blocks overlap by 75%
+const BLOCK_STEP: f64 = BLOCK_DURATION / 4.0;
+
+/// Blocks quieter than this (in LUFS) are silence, and ignored
+const ABSOLUTE_GATE: f64 = -70.0;
+
+/// Blocks more than this many LU below the ungated loudness are ignored
+const RELATIVE_GATE: f64 = 10.0;
+
+/// A second-order IIR filter, with coefficients normalized so that a0 is 1
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+
+    /// The previous two inputs and outputs
+    x: [f64; 2],
+    y: [f64; 2],
+}
+impl Biquad {
+    fn new(b: [f64; 3], a: [f64; 2]) -> Self {
+        Self {
+            b,
+            a,
+            x: [0.0; 2],
+            y: [0.0; 2],
+        }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+        y
+    }
+}
+
+/// Builds the two stages of the K-weighting filter: a high shelf modelling the head, then a
+/// high pass
+///
+/// BS.1770 only lists coefficients for 48kHz, so they are derived from the analog prototypes
+/// for other sample rates
+fn k_weighting(sample_rate: u32) -> [Biquad; 2] {
+    let fs = sample_rate as f64;
+
+    let k = (PI * 1681.974450955533 / fs).tan();
+    let q = 0.7071752369554196;
+    let vh = 10_f64.powf(3.999843853973347 / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad::new(
+        [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    let k = (PI * 38.13547087602444 / fs).tan();
+    let q = 0.5003270373238773;
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad::new(
+        [1.0, -2.0, 1.0],
+        [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    [shelf, high_pass]
+}
+
+fn to_lufs(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.log10()
+}
+
+/// Measures the integrated loudness (in LUFS) of interleaved samples, as specified by ITU-R
+/// BS.1770. Simplified in that every channel has a weight of 1, ie surround channels aren't
+/// weighted
+///
+/// Returns `None` if there is less than one gating block (400ms) of audio, or if it is all
+/// below the absolute gate, ie silent.
+pub fn integrated_loudness(samples: &[f32], sample_rate: u32, channels: u16) -> Option<f64> {
+    let channels = channels as usize;
+    if channels == 0 || sample_rate == 0 {
+        return None;
+    }
+
+    // The K-weighted energy of each sample frame, summed across channels
+    let mut filters = vec![k_weighting(sample_rate); channels];
+    let energies: Vec<f64> = samples
+        .chunks_exact(channels)
+        .map(|frame| {
+            frame
+                .iter()
+                .zip(&mut filters)
+                .map(|(&sample, [shelf, high_pass])| {
+                    let weighted = high_pass.process(shelf.process(sample as f64));
+                    weighted * weighted
+                })
+                .sum()
+        })
+        .collect();
+
+    let block_length = (BLOCK_DURATION * sample_rate as f64).round() as usize;
+    let step = (BLOCK_STEP * sample_rate as f64).round() as usize;
+    // At a few Hz, a step rounds down to no samples at all
+    if block_length == 0 || step == 0 || energies.len() < block_length {
+        return None;
+    }
+
+    let blocks: Vec<f64> = (0..=energies.len() - block_length)
+        .step_by(step)
+        .map(|start| {
+            energies[start..start + block_length].iter().sum::<f64>() / block_length as f64
+        })
+        .filter(|&block| to_lufs(block) > ABSOLUTE_GATE)
+        .collect();
+    if blocks.is_empty() {
+        return None;
+    }
+
+    let ungated = to_lufs(blocks.iter().sum::<f64>() / blocks.len() as f64);
+    let gated: Vec<f64> = blocks
+        .into_iter()
+        .filter(|&block| to_lufs(block) > ungated - RELATIVE_GATE)
+        .collect();
+
+    Some(to_lufs(gated.iter().sum::<f64>() / gated.len() as f64))
+}
+
+/// Returns the gain (in dB) that brings interleaved samples to `target_lufs`, eg -14 LUFS for
+/// most streaming services
+///
+/// Returns `None` when the loudness can't be measured (see `integrated_loudness`)
+pub fn normalization_gain(
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+    target_lufs: f64,
+) -> Option<f64> {
+    integrated_loudness(samples, sample_rate, channels).map(|loudness| target_lufs - loudness)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 1kHz stereo sine with the given peak amplitude (in dBFS)
+    fn sine(level: f64, seconds: f64) -> Vec<f32> {
+        let amplitude = 10_f64.powf(level / 20.0);
+        (0..(48_000.0 * seconds) as usize)
+            .flat_map(|index| {
+                let sample = amplitude * (2.0 * PI * 1000.0 * index as f64 / 48_000.0).sin();
+                [sample as f32; 2]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_integrated_loudness_reference_tone() {
+        // EBU Tech 3341: a -23 dBFS 1kHz stereo sine measures -23 LUFS
+        let loudness = integrated_loudness(&sine(-23.0, 5.0), 48_000, 2).unwrap();
+        assert!((loudness + 23.0).abs() < 0.1, "{loudness}");
+
+        assert_eq!(integrated_loudness(&[0.0; 96_000], 48_000, 2), None);
+        assert_eq!(integrated_loudness(&sine(-23.0, 0.1), 48_000, 2), None);
+    }
+
+    #[test]
+    fn test_integrated_loudness_tiny_sample_rate() {
+        let samples = [0.5, -0.5].repeat(100);
+        for sample_rate in [0, 1, 2, 4] {
+            assert_eq!(integrated_loudness(&samples, sample_rate, 2), None);
+        }
+    }
+
+    #[test]
+    fn test_normalization_gain() {
+        let samples = sine(-30.0, 5.0);
+        let gain = normalization_gain(&samples, 48_000, 2, -14.0).unwrap();
+        assert!(gain > 0.0);
+
+        let scale = 10_f64.powf(gain / 20.0) as f32;
+        let normalized: Vec<f32> = samples.iter().map(|sample| sample * scale).collect();
+        let loudness = integrated_loudness(&normalized, 48_000, 2).unwrap();
+
+        assert!((loudness + 14.0).abs() < 0.1, "{loudness}");
+    }
+}
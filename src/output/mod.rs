@@ -0,0 +1,8 @@
+pub mod deemphasis;
+pub mod downmix;
+pub mod hash;
+pub mod loudness;
+pub mod pcm;
+pub mod peak;
+pub mod wav;
+pub mod waveform;
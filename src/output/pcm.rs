@@ -0,0 +1,67 @@
+use std::io::{self, Write};
+
+/// The byte order of 16-bit PCM samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    /// Used by WAV files and most sound cards
+    Little,
+
+    /// Expected by some embedded DACs
+    Big,
+}
+impl Endianness {
+    pub(crate) fn u16_bytes(&self, value: u16) -> [u8; 2] {
+        match self {
+            Self::Little => value.to_le_bytes(),
+            Self::Big => value.to_be_bytes(),
+        }
+    }
+
+    pub(crate) fn u32_bytes(&self, value: u32) -> [u8; 4] {
+        match self {
+            Self::Little => value.to_le_bytes(),
+            Self::Big => value.to_be_bytes(),
+        }
+    }
+}
+
+/// Converts a sample to i16, clamping it to -1.0..=1.0 first
+pub(crate) fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+}
+
+/// Writes interleaved samples as raw 16-bit PCM (no header) to `writer`, eg to pipe into
+/// another tool
+///
+/// Samples are clamped to -1.0..=1.0 before being converted to i16.
+pub fn write_pcm_to<W: Write>(
+    writer: &mut W,
+    samples: &[f32],
+    endianness: Endianness,
+) -> io::Result<()> {
+    for &sample in samples {
+        writer.write_all(&endianness.u16_bytes(to_i16(sample) as u16))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_pcm_to_endianness() {
+        let samples = [0.0, 0.5, -0.5, 1.0];
+        let mut little = Vec::new();
+        write_pcm_to(&mut little, &samples, Endianness::Little).unwrap();
+        let mut big = Vec::new();
+        write_pcm_to(&mut big, &samples, Endianness::Big).unwrap();
+
+        assert_eq!(little.len(), samples.len() * 2);
+        assert_eq!(little, [0x00, 0x00, 0xFF, 0x3F, 0x01, 0xC0, 0xFF, 0x7F]);
+        for (little, big) in little.chunks_exact(2).zip(big.chunks_exact(2)) {
+            assert_eq!(little, [big[1], big[0]]);
+        }
+    }
+}
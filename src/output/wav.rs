@@ -0,0 +1,212 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::pcm::{Endianness, to_i16};
+
+/// Size of the RIFF header, `fmt ` chunk and `data` chunk header of a canonical WAV file
+const HEADER_LENGTH: u32 = 44;
+
+const BITS_PER_SAMPLE: u16 = 16;
+
+/// Writes interleaved samples to a 16-bit PCM WAV file at `path`
+///
+/// `sample_rate` and `channels` would usually come from the stream's first frame, ie
+/// `header.sample_rate` and `header.channel_mode().channel_count()`
+pub fn write_wav(
+    path: impl AsRef<Path>,
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_wav_to(
+        &mut writer,
+        samples,
+        sample_rate,
+        channels,
+        Endianness::Little,
+    )?;
+    writer.flush()
+}
+
+/// Writes interleaved samples as a 16-bit PCM WAV to `writer`
+///
+/// WAV files are little-endian. With `Endianness::Big`, the file is written in the big-endian
+/// RIFX variant instead: the magic is "RIFX", and every field and sample is big-endian.
+///
+/// Samples are clamped to -1.0..=1.0 before being converted to i16. The chunk sizes are written
+/// as placeholders and patched once the samples have been written. Returns an `InvalidInput`
+/// error if there are too many samples for the 32-bit chunk sizes, or if the channels and sample
+/// rate don't fit the block align and byte rate fields.
+///
+/// Structure (all fields little-endian):
+/// bytes\[0..4]   => "RIFF" ("RIFX" if big-endian)
+/// bytes\[4..8]   => size of the rest of the file
+/// bytes\[8..12]  => "WAVE"
+/// bytes\[12..16] => "fmt "
+/// bytes\[16..20] => size of the rest of the `fmt ` chunk (16)
+/// bytes\[20..22] => audio format (1, PCM)
+/// bytes\[22..24] => channels
+/// bytes\[24..28] => sample rate
+/// bytes\[28..32] => byte rate
+/// bytes\[32..34] => block align, ie bytes per sample frame
+/// bytes\[34..36] => bits per sample
+/// bytes\[36..40] => "data"
+/// bytes\[40..44] => size of the samples
+/// bytes\[44..]   => the samples
+pub fn write_wav_to<W: Write + Seek>(
+    writer: &mut W,
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+    endianness: Endianness,
+) -> io::Result<()> {
+    // The RIFF size field is 32 bits, so the file can't hold much more than 2^31 samples
+    if samples.len() as u64 * 2 + (HEADER_LENGTH - 8) as u64 > u32::MAX as u64 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "too many samples for a WAV file",
+        ));
+    }
+
+    // Computed in wider types, as they may not fit their 16 and 32-bit fields
+    let block_align = u16::try_from(channels as u32 * BITS_PER_SAMPLE as u32 / 8);
+    let byte_rate =
+        u32::try_from(sample_rate as u64 * channels as u64 * BITS_PER_SAMPLE as u64 / 8);
+    let (Ok(block_align), Ok(byte_rate)) = (block_align, byte_rate) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "too many channels or too high a sample rate for a WAV file",
+        ));
+    };
+
+    let start = writer.stream_position()?;
+
+    let magic = match endianness {
+        Endianness::Little => b"RIFF",
+        Endianness::Big => b"RIFX",
+    };
+    writer.write_all(magic)?;
+    writer.write_all(&0_u32.to_le_bytes())?;
+    writer.write_all(b"WAVE")?;
+
+    writer.write_all(b"fmt ")?;
+    writer.write_all(&endianness.u32_bytes(16))?;
+    writer.write_all(&endianness.u16_bytes(1))?;
+    writer.write_all(&endianness.u16_bytes(channels))?;
+    writer.write_all(&endianness.u32_bytes(sample_rate))?;
+    writer.write_all(&endianness.u32_bytes(byte_rate))?;
+    writer.write_all(&endianness.u16_bytes(block_align))?;
+    writer.write_all(&endianness.u16_bytes(BITS_PER_SAMPLE))?;
+
+    writer.write_all(b"data")?;
+    writer.write_all(&0_u32.to_le_bytes())?;
+
+    let mut data_length: u32 = 0;
+    for &sample in samples {
+        writer.write_all(&endianness.u16_bytes(to_i16(sample) as u16))?;
+        data_length += 2;
+    }
+
+    // Patch the sizes now that the length of the data is known
+    let end = writer.stream_position()?;
+    writer.seek(SeekFrom::Start(start + 4))?;
+    writer.write_all(&endianness.u32_bytes(HEADER_LENGTH - 8 + data_length))?;
+    writer.seek(SeekFrom::Start(start + 40))?;
+    writer.write_all(&endianness.u32_bytes(data_length))?;
+    writer.seek(SeekFrom::Start(end))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn read_u16(bytes: &[u8], start: usize) -> u16 {
+        u16::from_le_bytes([bytes[start], bytes[start + 1]])
+    }
+
+    fn read_u32(bytes: &[u8], start: usize) -> u32 {
+        u32::from_le_bytes(bytes[start..start + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_write_wav_to() {
+        let samples = [0.0, 0.5, -0.5, 1.0, -2.0, 2.0];
+        let mut cursor = Cursor::new(Vec::new());
+        write_wav_to(&mut cursor, &samples, 44100, 2, Endianness::Little).unwrap();
+        let bytes = cursor.into_inner();
+
+        assert_eq!(bytes.len(), 44 + 12);
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(read_u32(&bytes, 4), 36 + 12);
+        assert_eq!(&bytes[8..16], b"WAVEfmt ");
+        assert_eq!(read_u32(&bytes, 16), 16);
+        assert_eq!(read_u16(&bytes, 20), 1);
+        assert_eq!(read_u16(&bytes, 22), 2);
+        assert_eq!(read_u32(&bytes, 24), 44100);
+        assert_eq!(read_u32(&bytes, 28), 44100 * 4);
+        assert_eq!(read_u16(&bytes, 32), 4);
+        assert_eq!(read_u16(&bytes, 34), 16);
+        assert_eq!(&bytes[36..40], b"data");
+        assert_eq!(read_u32(&bytes, 40), 12);
+
+        // Out of range samples are clamped
+        let samples: Vec<i16> = bytes[44..]
+            .chunks_exact(2)
+            .map(|sample| i16::from_le_bytes([sample[0], sample[1]]))
+            .collect();
+        assert_eq!(samples, [0, 16383, -16383, 32767, -32767, 32767]);
+    }
+
+    #[test]
+    fn test_write_wav_to_big_endian() {
+        let samples = [0.0, 0.5, -0.5, 1.0];
+        let mut little = Cursor::new(Vec::new());
+        write_wav_to(&mut little, &samples, 44100, 2, Endianness::Little).unwrap();
+        let mut big = Cursor::new(Vec::new());
+        write_wav_to(&mut big, &samples, 44100, 2, Endianness::Big).unwrap();
+        let (little, big) = (little.into_inner(), big.into_inner());
+
+        assert_eq!(&big[0..4], b"RIFX");
+        assert_eq!(u32::from_be_bytes(big[24..28].try_into().unwrap()), 44100);
+        assert_eq!(u32::from_be_bytes(big[40..44].try_into().unwrap()), 8);
+
+        // The samples are byte-swapped
+        for (little, big) in little[44..].chunks_exact(2).zip(big[44..].chunks_exact(2)) {
+            assert_eq!(little, [big[1], big[0]]);
+        }
+    }
+
+    #[test]
+    fn test_write_wav_to_many_channels() {
+        // 4096 channels overflow `channels * 16` in a u16, but a block align of 8192 fits
+        let mut cursor = Cursor::new(Vec::new());
+        write_wav_to(&mut cursor, &[0.0; 4096], 8000, 4096, Endianness::Little).unwrap();
+        assert_eq!(read_u16(&cursor.into_inner(), 32), 8192);
+
+        let mut cursor = Cursor::new(Vec::new());
+        let error = write_wav_to(&mut cursor, &[], 8000, u16::MAX, Endianness::Little);
+        assert_eq!(error.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+
+        let error = write_wav_to(&mut cursor, &[], u32::MAX, 2, Endianness::Little);
+        assert_eq!(error.unwrap_err().kind(), io::ErrorKind::InvalidInput);
+        assert!(cursor.into_inner().is_empty());
+    }
+
+    #[test]
+    fn test_write_wav() {
+        let path = std::env::temp_dir().join("mp3_decoder_test_write_wav.wav");
+        write_wav(&path, &[0.25; 8], 22050, 1).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(bytes.len(), 44 + 16);
+        assert_eq!(read_u16(&bytes, 22), 1);
+        assert_eq!(read_u32(&bytes, 24), 22050);
+        assert_eq!(read_u32(&bytes, 40), 16);
+    }
+}
@@ -0,0 +1,53 @@
+/// Downsamples interleaved samples to `buckets` (min, max) pairs, eg one per pixel of a
+/// waveform overview
+///
+/// Every channel is folded into the same bucket. Sample frames are spread as evenly as possible,
+/// so with fewer frames than buckets some buckets are empty, and hold (0.0, 0.0).
+pub fn waveform(samples: &[f32], channels: u16, buckets: usize) -> Vec<(f32, f32)> {
+    let channels = channels.max(1) as usize;
+    let frame_count = samples.len() / channels;
+
+    (0..buckets)
+        .map(|bucket| {
+            let start = bucket * frame_count / buckets * channels;
+            let end = (bucket + 1) * frame_count / buckets * channels;
+
+            samples[start..end]
+                .iter()
+                .fold(None, |range: Option<(f32, f32)>, &sample| match range {
+                    Some((min, max)) => Some((min.min(sample), max.max(sample))),
+                    None => Some((sample, sample)),
+                })
+                .unwrap_or((0.0, 0.0))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_waveform() {
+        let samples: Vec<f32> = (0..1000)
+            .flat_map(|index| {
+                let sample = (index as f32 / 50.0).sin();
+                [sample, -sample]
+            })
+            .collect();
+        let waveform = waveform(&samples, 2, 64);
+
+        assert_eq!(waveform.len(), 64);
+        assert!(waveform.iter().all(|(min, max)| min <= max));
+
+        // Both channels are folded in, so each bucket is symmetric
+        assert!(waveform.iter().all(|(min, max)| *min == -max));
+    }
+
+    #[test]
+    fn test_waveform_more_buckets_than_samples() {
+        let waveform = waveform(&[0.5, -0.5], 1, 4);
+
+        assert_eq!(waveform, [(0.0, 0.0), (0.5, 0.5), (0.0, 0.0), (-0.5, -0.5)]);
+    }
+}
@@ -0,0 +1,66 @@
+use std::io::{self, Cursor, Read};
+
+use crate::audio::frame::MP3AudioFrame;
+
+/// Builds an audio frame with the given header, padded out to its frame length with zeros
+pub(crate) fn build_frame(header_bytes: [u8; 4]) -> Vec<u8> {
+    // Room for the CRC, if any
+    let header_and_crc = [&header_bytes[..], &[0x00; 2]].concat();
+    let frame_length = MP3AudioFrame::from_bytes(&header_and_crc)
+        .unwrap()
+        .frame_length;
+
+    let mut frame = vec![0; frame_length as usize];
+    frame[..4].copy_from_slice(&header_bytes);
+    frame
+}
+
+/// Builds a stream of `count` identical frames
+pub(crate) fn build_stream(header_bytes: [u8; 4], count: usize) -> Vec<u8> {
+    build_frame(header_bytes).repeat(count)
+}
+
+/// Builds an ID3v2.3 metadata frame with no flags set
+pub(crate) fn build_metadata_frame(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut frame = id.to_vec();
+    frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&[0x00, 0x00]);
+    frame.extend_from_slice(data);
+    frame
+}
+
+/// Builds an ID3v2.3 tag holding `frames`, with no flags set
+pub(crate) fn build_tag(frames: &[Vec<u8>]) -> Vec<u8> {
+    let body = frames.concat();
+    let size = body.len() as u32;
+
+    let mut tag = vec![b'I', b'D', b'3', 0x03, 0x00, 0x00];
+    tag.extend_from_slice(&[
+        ((size >> 21) & 0x7F) as u8,
+        ((size >> 14) & 0x7F) as u8,
+        ((size >> 7) & 0x7F) as u8,
+        (size & 0x7F) as u8,
+    ]);
+    tag.extend_from_slice(&body);
+    tag
+}
+
+/// Returns at most `chunk_size` bytes per read, like a socket, so data spans several reads
+pub(crate) struct ChunkedReader {
+    cursor: Cursor<Vec<u8>>,
+    chunk_size: usize,
+}
+impl ChunkedReader {
+    pub(crate) fn new(bytes: Vec<u8>, chunk_size: usize) -> Self {
+        Self {
+            cursor: Cursor::new(bytes),
+            chunk_size,
+        }
+    }
+}
+impl Read for ChunkedReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let length = buf.len().min(self.chunk_size);
+        self.cursor.read(&mut buf[..length])
+    }
+}
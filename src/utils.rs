@@ -24,3 +24,33 @@ impl fmt::Display for HexSlice<'_> {
     }
 }
 
+/// Reads big-endian bit fields from a byte slice, most significant bit first
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    bit_position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            bit_position: 0,
+        }
+    }
+
+    /// Reads the next `count` bits (at most 32). Bits past the end of the slice read as 0
+    pub fn read(&mut self, count: u8) -> u32 {
+        let mut value = 0;
+        for _ in 0..count {
+            let byte = self.bytes.get(self.bit_position / 8).copied().unwrap_or(0);
+            let bit = (byte >> (7 - self.bit_position % 8)) & 0b1;
+            value = (value << 1) | bit as u32;
+            self.bit_position += 1;
+        }
+        value
+    }
+
+    pub fn read_bool(&mut self) -> bool {
+        self.read(1) == 1
+    }
+}